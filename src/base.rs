@@ -5,6 +5,7 @@ use std::ffi::OsStr;
 use crate::{
     common::*,
     error::{Error, Result},
+    kind::{EmitterMode, LaserPowerMode},
 };
 
 #[cfg(feature = "with-image")]
@@ -267,8 +268,71 @@ pub struct Resolution {
     pub width: usize,
     pub height: usize,
 }
+
+/// A rectangular region of an image in pixels.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Statistics of valid depth values in a region, in meters.
+///
+/// Pixels with zero depth are regarded as invalid and excluded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionStats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub median: f32,
+    pub valid_count: usize,
+}
+
+/// The metadata attached to a frame.
+///
+/// Fields are `None` if the metadata is not supported by the frame.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameMetadata {
+    pub frame_counter: Option<u64>,
+    /// The frame timestamp in microseconds.
+    pub frame_timestamp: Option<u64>,
+    /// The sensor timestamp in microseconds.
+    pub sensor_timestamp: Option<u64>,
+    pub actual_exposure: Option<Duration>,
+    pub gain_level: Option<u64>,
+    pub auto_exposure: Option<bool>,
+    pub white_balance: Option<u64>,
+    /// The time of arrival in milliseconds in system clock.
+    pub time_of_arrival: Option<u64>,
+    /// The temperature in degrees Celsius.
+    pub temperature: Option<f32>,
+    /// The timestamp of the backend in milliseconds.
+    pub backend_timestamp: Option<u64>,
+    pub actual_fps: Option<u64>,
+    pub laser_power: Option<u64>,
+    pub laser_power_mode: Option<LaserPowerMode>,
+    pub exposure_priority: Option<bool>,
+    /// The region of interest of auto exposure, which is `None` if the bounds are reversed.
+    pub exposure_roi: Option<Rect>,
+    pub brightness: Option<i64>,
+    pub contrast: Option<i64>,
+    pub saturation: Option<i64>,
+    pub sharpness: Option<i64>,
+    pub auto_white_balance_temperature: Option<u64>,
+    pub backlight_compensation: Option<i64>,
+    pub hue: Option<i64>,
+    pub gamma: Option<i64>,
+    pub manual_white_balance: Option<u64>,
+    pub power_line_frequency: Option<u64>,
+    pub low_light_compensation: Option<bool>,
+    pub emitter_mode: Option<EmitterMode>,
+    pub led_power: Option<u64>,
+}
+
 /// Represents the specification of a stream.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StreamProfileData {
     pub stream: StreamKind,
     pub format: Format,
@@ -285,37 +349,56 @@ mod rs2_image {
     ///
     /// This is a wrapper of various [ImageBuffer](image::ImageBuffer) variants.
     /// It pixel data is stored in slice for better performance.
+    ///
+    /// The [RgbF32](Rs2Image::RgbF32) variant stores the x, y and z coordinates of
+    /// [Xyz32F](Format::Xyz32F) frames in the red, green and blue channels respectively.
     #[derive(Debug, Clone)]
     pub enum Rs2Image<'a> {
         Bgr8(ImageBuffer<Bgr<u8>, &'a [u8]>),
         Bgra8(ImageBuffer<Bgra<u8>, &'a [u8]>),
         Rgb8(ImageBuffer<Rgb<u8>, &'a [u8]>),
         Rgba8(ImageBuffer<Rgba<u8>, &'a [u8]>),
+        Luma8(ImageBuffer<Luma<u8>, &'a [u8]>),
         Luma16(ImageBuffer<Luma<u16>, &'a [u16]>),
+        LumaF32(ImageBuffer<Luma<f32>, &'a [f32]>),
+        RgbF32(ImageBuffer<Rgb<f32>, &'a [f32]>),
     }
 
-    /// Creates an owned image by coping underlying buffer.
     impl<'a> Rs2Image<'a> {
-        pub fn to_owned(&self) -> DynamicImage {
-            self.into()
+        /// Creates an owned image by coping underlying buffer.
+        ///
+        /// It returns error on floating point images, which are not supported by [DynamicImage].
+        pub fn to_owned(&self) -> Result<DynamicImage> {
+            DynamicImage::try_from(self)
         }
     }
 
-    impl<'a> From<&Rs2Image<'a>> for DynamicImage {
-        fn from(from: &Rs2Image<'a>) -> DynamicImage {
-            match from {
+    impl<'a> TryFrom<&Rs2Image<'a>> for DynamicImage {
+        type Error = Error;
+
+        fn try_from(from: &Rs2Image<'a>) -> Result<DynamicImage> {
+            let image = match from {
                 Rs2Image::Bgr8(image) => DynamicImage::ImageBgr8(image.convert()),
                 Rs2Image::Bgra8(image) => DynamicImage::ImageBgra8(image.convert()),
                 Rs2Image::Rgb8(image) => DynamicImage::ImageRgb8(image.convert()),
                 Rs2Image::Rgba8(image) => DynamicImage::ImageRgba8(image.convert()),
+                Rs2Image::Luma8(image) => DynamicImage::ImageLuma8(image.convert()),
                 Rs2Image::Luma16(image) => DynamicImage::ImageLuma16(image.convert()),
-            }
+                Rs2Image::LumaF32(_) | Rs2Image::RgbF32(_) => {
+                    return Err(Error::InvalidData(
+                        "floating point images cannot be converted to DynamicImage".into(),
+                    ))
+                }
+            };
+            Ok(image)
         }
     }
 
-    impl<'a> From<Rs2Image<'a>> for DynamicImage {
-        fn from(from: Rs2Image<'a>) -> DynamicImage {
-            (&from).into()
+    impl<'a> TryFrom<Rs2Image<'a>> for DynamicImage {
+        type Error = Error;
+
+        fn try_from(from: Rs2Image<'a>) -> Result<DynamicImage> {
+            DynamicImage::try_from(&from)
         }
     }
 }
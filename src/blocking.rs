@@ -0,0 +1,144 @@
+//! Runs the blocking waits behind asynchronous methods.
+//!
+//! The blocking calls are dispatched to the first available backend:
+//!
+//! - `tokio::task::spawn_blocking` if the `tokio` feature is enabled and the caller
+//!   runs within a tokio runtime.
+//! - `async_std::task::spawn_blocking` if the `async-std` feature is enabled.
+//! - The crate-managed waiter thread pool otherwise.
+//!
+//! The size of the tokio and async-std pools is configured by the runtimes, while the
+//! crate-managed pool is limited by [set_max_waiter_threads].
+
+use crate::common::*;
+use std::{
+    collections::VecDeque,
+    sync::{Condvar, Once},
+};
+
+/// The default maximum number of threads in the waiter thread pool.
+pub const DEFAULT_MAX_WAITER_THREADS: usize = 16;
+
+/// The time an idle waiter thread is kept before it exits.
+const WAITER_KEEP_ALIVE: Duration = Duration::from_secs(10);
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+struct Pool {
+    state: Mutex<PoolState>,
+    condvar: Condvar,
+}
+
+struct PoolState {
+    jobs: VecDeque<Job>,
+    num_threads: usize,
+    num_idle: usize,
+    max_threads: usize,
+}
+
+/// Gets the crate-managed pool, which is lazily created and never freed.
+fn pool() -> &'static Pool {
+    static POOL: AtomicPtr<Pool> = AtomicPtr::new(ptr::null_mut());
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| {
+        let pool = Pool {
+            state: Mutex::new(PoolState {
+                jobs: VecDeque::new(),
+                num_threads: 0,
+                num_idle: 0,
+                max_threads: DEFAULT_MAX_WAITER_THREADS,
+            }),
+            condvar: Condvar::new(),
+        };
+        POOL.store(Box::into_raw(Box::new(pool)), Ordering::Release);
+    });
+    // the pointer is set once by call_once() above and lives to the end of program
+    unsafe { &*POOL.load(Ordering::Acquire) }
+}
+
+/// Sets the maximum number of threads in the crate-managed waiter thread pool.
+///
+/// The waits exceeding the limit are queued until a thread is available.
+/// Existing threads are not stopped if the limit is lowered. The value is clamped to at least 1.
+///
+/// Every pending wait holds a thread until it completes or its future is dropped, after which
+/// the thread is given back within 100 milliseconds. Waits without timeout on a stalled device
+/// never complete, so keeping as many of them as the limit starves all later waits.
+pub fn set_max_waiter_threads(max_threads: usize) {
+    pool().state.lock().unwrap().max_threads = max_threads.max(1);
+}
+
+/// Gets the maximum number of threads in the crate-managed waiter thread pool.
+pub fn max_waiter_threads() -> usize {
+    pool().state.lock().unwrap().max_threads
+}
+
+/// Runs a blocking function on the selected backend.
+pub(crate) fn spawn_blocking<F>(func: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    #[cfg(feature = "tokio")]
+    {
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn_blocking(func);
+            return;
+        }
+    }
+
+    #[cfg(feature = "async-std")]
+    {
+        async_std::task::spawn_blocking(func);
+    }
+
+    #[cfg(not(feature = "async-std"))]
+    {
+        spawn_on_pool(Box::new(func));
+    }
+}
+
+#[cfg_attr(feature = "async-std", allow(dead_code))]
+fn spawn_on_pool(job: Job) {
+    let mut state = pool().state.lock().unwrap();
+    state.jobs.push_back(job);
+
+    if state.num_idle == 0 && state.num_threads < state.max_threads {
+        state.num_threads += 1;
+        drop(state);
+        thread::spawn(waiter_thread);
+    } else {
+        drop(state);
+        pool().condvar.notify_one();
+    }
+}
+
+fn waiter_thread() {
+    let mut state = pool().state.lock().unwrap();
+
+    loop {
+        match state.jobs.pop_front() {
+            Some(job) => {
+                drop(state);
+                // a panicking job must not take down the thread count
+                let _ = panic::catch_unwind(panic::AssertUnwindSafe(job));
+                state = pool().state.lock().unwrap();
+            }
+            None => {
+                state.num_idle += 1;
+                let (new_state, result) = pool()
+                    .condvar
+                    .wait_timeout(state, WAITER_KEEP_ALIVE)
+                    .unwrap();
+                state = new_state;
+                state.num_idle -= 1;
+
+                if result.timed_out() && state.jobs.is_empty() {
+                    break;
+                }
+            }
+        }
+    }
+
+    state.num_threads -= 1;
+}
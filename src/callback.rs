@@ -0,0 +1,69 @@
+//! Helpers to pass Rust closures as librealsense callbacks.
+
+use crate::{
+    common::*,
+    frame::{AnyFrame, GenericFrameEx},
+};
+
+/// The owned user data passed to a librealsense callback.
+///
+/// The data must outlive the registration of callback.
+#[derive(Debug)]
+pub(crate) struct CallbackContext {
+    ptr: NonNull<c_void>,
+    drop_fn: unsafe fn(NonNull<c_void>),
+}
+
+impl CallbackContext {
+    pub(crate) fn new<T>(data: Box<T>) -> Self
+    where
+        T: Send + 'static,
+    {
+        let ptr = NonNull::new(Box::into_raw(data)).unwrap().cast::<c_void>();
+        Self {
+            ptr,
+            drop_fn: drop_data::<T>,
+        }
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut c_void {
+        self.ptr.as_ptr()
+    }
+}
+
+impl Drop for CallbackContext {
+    fn drop(&mut self) {
+        unsafe {
+            (self.drop_fn)(self.ptr);
+        }
+    }
+}
+
+unsafe impl Send for CallbackContext {}
+
+/// Creates a frame callback calling `func`, along with the context owning `func`.
+pub(crate) fn new_frame_callback<F>(func: F) -> (sys::rs2_frame_callback_ptr, CallbackContext)
+where
+    F: FnMut(AnyFrame) + Send + 'static,
+{
+    let context = CallbackContext::new(Box::new(func));
+    (Some(frame_callback::<F>), context)
+}
+
+/// The `rs2_frame_callback_ptr` calling a closure of type `F` stored in the user data.
+unsafe extern "C" fn frame_callback<F>(frame: *mut sys::rs2_frame, data: *mut c_void)
+where
+    F: FnMut(AnyFrame) + Send + 'static,
+{
+    // unwinding across FFI boundary is undefined behavior
+    let _ = panic::catch_unwind(|| {
+        // the frame is owned by the callback
+        let frame = AnyFrame::from_raw(frame);
+        let func = &mut *(data as *mut F);
+        func(frame);
+    });
+}
+
+unsafe fn drop_data<T>(data: NonNull<c_void>) {
+    drop(Box::from_raw(data.cast::<T>().as_ptr()));
+}
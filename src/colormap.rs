@@ -0,0 +1,343 @@
+//! Pure-Rust depth colorization.
+//!
+//! Unlike [Colorizer](crate::processing_block::Colorizer), it works on plain metric
+//! depth buffers, such as depth images loaded from disk.
+
+use crate::{
+    base::Resolution,
+    common::*,
+    decode::RgbImage,
+    error::{Error, Result},
+    frame::{DepthFrame, DepthFrameEx, VideoFrameEx},
+};
+
+/// The number of bins used by histogram equalization.
+const HISTOGRAM_SIZE: usize = 4096;
+
+/// The color palettes mapping normalized depth to colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Colormap {
+    Turbo,
+    Viridis,
+    Grayscale,
+}
+
+impl Colormap {
+    /// Gets the color at position `t` in range `[0, 1]`.
+    ///
+    /// Turbo and viridis are computed by polynomial approximations of the original palettes.
+    pub fn color(&self, t: f32) -> [u8; 3] {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Turbo => {
+                let r = polynomial(
+                    t,
+                    &[
+                        0.135_721_38,
+                        4.615_392_6,
+                        -42.660_324,
+                        132.131_08,
+                        -152.942_4,
+                        59.286_38,
+                    ],
+                );
+                let g = polynomial(
+                    t,
+                    &[
+                        0.091_402_61,
+                        2.194_188_4,
+                        4.842_966_6,
+                        -14.185_033,
+                        4.277_298_5,
+                        2.829_566,
+                    ],
+                );
+                let b = polynomial(
+                    t,
+                    &[
+                        0.106_673_3,
+                        12.641_946,
+                        -60.582_05,
+                        110.362_77,
+                        -89.903_11,
+                        27.348_25,
+                    ],
+                );
+                [to_u8(r), to_u8(g), to_u8(b)]
+            }
+            Self::Viridis => {
+                let r = polynomial(
+                    t,
+                    &[
+                        0.277_727_33,
+                        0.105_093_04,
+                        -0.330_861_83,
+                        -4.634_230_6,
+                        6.228_27,
+                        4.776_385,
+                        -5.435_456,
+                    ],
+                );
+                let g = polynomial(
+                    t,
+                    &[
+                        0.005_407_344_5,
+                        1.404_613_5,
+                        0.214_847_56,
+                        -5.799_101,
+                        14.179_933,
+                        -13.745_145,
+                        4.645_852_6,
+                    ],
+                );
+                let b = polynomial(
+                    t,
+                    &[
+                        0.334_099_8,
+                        1.384_590_2,
+                        0.095_095_16,
+                        -19.332_441,
+                        56.690_55,
+                        -65.353_035,
+                        26.312_435,
+                    ],
+                );
+                [to_u8(r), to_u8(g), to_u8(b)]
+            }
+            Self::Grayscale => {
+                let value = to_u8(t);
+                [value, value, value]
+            }
+        }
+    }
+}
+
+/// Converts metric depth to color images.
+///
+/// Distances are linearly mapped from the range `[min_distance, max_distance]` to
+/// the colormap by default, or mapped by the cumulative histogram if histogram equalization
+/// is enabled. Pixels with zero or non-finite depth are painted black.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthColorizer {
+    pub colormap: Colormap,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    pub histogram_equalization: bool,
+}
+
+impl DepthColorizer {
+    /// Creates a colorizer mapping distances from 0 to 6 meters.
+    pub fn new(colormap: Colormap) -> Self {
+        Self {
+            colormap,
+            min_distance: 0.0,
+            max_distance: 6.0,
+            histogram_equalization: false,
+        }
+    }
+
+    /// Sets the distance range in meters.
+    pub fn with_range(mut self, min_distance: f32, max_distance: f32) -> Self {
+        self.min_distance = min_distance;
+        self.max_distance = max_distance;
+        self
+    }
+
+    /// Enables or disables histogram equalization.
+    pub fn with_histogram_equalization(mut self, enabled: bool) -> Self {
+        self.histogram_equalization = enabled;
+        self
+    }
+
+    /// Colorizes a [Z16](Format::Z16) depth frame.
+    pub fn colorize_frame(&self, frame: &DepthFrame) -> Result<RgbImage> {
+        let Resolution { width, height } = frame.resolution()?;
+        let distances = frame.to_meters_vec()?;
+        self.colorize(&distances, width, height)
+    }
+
+    /// Colorizes raw depth values, where `depth_units` is the length in meters per unit.
+    pub fn colorize_raw(
+        &self,
+        values: &[u16],
+        width: usize,
+        height: usize,
+        depth_units: f32,
+    ) -> Result<RgbImage> {
+        let distances: Vec<_> = values
+            .iter()
+            .map(|&value| value as f32 * depth_units)
+            .collect();
+        self.colorize(&distances, width, height)
+    }
+
+    /// Colorizes distances in meters stored in row-major order.
+    pub fn colorize(&self, distances: &[f32], width: usize, height: usize) -> Result<RgbImage> {
+        if distances.len() != width * height {
+            return Err(Error::InvalidData(format!(
+                "expect {} distances for {}x{} image, but got {}",
+                width * height,
+                width,
+                height,
+                distances.len()
+            )));
+        }
+        let is_range_valid = self.min_distance.is_finite()
+            && self.max_distance.is_finite()
+            && self.min_distance < self.max_distance;
+        if !is_range_valid {
+            return Err(Error::InvalidData(format!(
+                "invalid distance range [{}, {}]",
+                self.min_distance, self.max_distance
+            )));
+        }
+
+        let range = self.max_distance - self.min_distance;
+        let bin_of = |dist: f32| {
+            let t = ((dist - self.min_distance) / range).clamp(0.0, 1.0);
+            ((t * (HISTOGRAM_SIZE - 1) as f32) as usize).min(HISTOGRAM_SIZE - 1)
+        };
+        let is_valid = |dist: f32| dist.is_finite() && dist > 0.0;
+
+        // cumulative histogram normalized to [0, 1]
+        let cdf = if self.histogram_equalization {
+            let mut histogram = vec![0usize; HISTOGRAM_SIZE];
+            distances
+                .iter()
+                .copied()
+                .filter(|&dist| is_valid(dist))
+                .for_each(|dist| histogram[bin_of(dist)] += 1);
+            let total: usize = histogram.iter().sum();
+            let mut sum = 0;
+            let cdf: Vec<f32> = histogram
+                .iter()
+                .map(|&count| {
+                    sum += count;
+                    sum as f32 / total.max(1) as f32
+                })
+                .collect();
+            Some(cdf)
+        } else {
+            None
+        };
+
+        let samples: Vec<u8> = distances
+            .iter()
+            .flat_map(|&dist| {
+                if !is_valid(dist) {
+                    return [0; 3];
+                }
+                let t = match &cdf {
+                    Some(cdf) => cdf[bin_of(dist)],
+                    None => (dist - self.min_distance) / range,
+                };
+                self.colormap.color(t)
+            })
+            .collect();
+
+        Ok(ImageBuffer::from_raw(width as u32, height as u32, samples).unwrap())
+    }
+}
+
+fn polynomial(t: f32, coefficients: &[f32]) -> f32 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0.0, |acc, &coefficient| acc * t + coefficient)
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn luminance([r, g, b]: [u8; 3]) -> f32 {
+        0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32
+    }
+
+    #[test]
+    fn turbo_colors() {
+        // dark blue, green and dark red at both ends and the middle
+        let [r, g, b] = Colormap::Turbo.color(0.0);
+        assert!(r < 64 && g < 64 && b < 64);
+        let [r, g, b] = Colormap::Turbo.color(0.5);
+        assert!(g > r && g > b);
+        let [r, g, b] = Colormap::Turbo.color(1.0);
+        assert!(r > g && r > b);
+
+        assert_eq!(Colormap::Turbo.color(-1.0), Colormap::Turbo.color(0.0));
+        assert_eq!(Colormap::Turbo.color(2.0), Colormap::Turbo.color(1.0));
+    }
+
+    #[test]
+    fn viridis_colors() {
+        // purple to yellow with increasing luminance
+        let [r, g, b] = Colormap::Viridis.color(0.0);
+        assert!(b > g && r > g);
+        let [r, g, b] = Colormap::Viridis.color(1.0);
+        assert!(r > b && g > b);
+
+        let luminances: Vec<_> = (0..=10)
+            .map(|step| luminance(Colormap::Viridis.color(step as f32 / 10.0)))
+            .collect();
+        assert!(luminances.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn grayscale_colors() {
+        assert_eq!(Colormap::Grayscale.color(0.0), [0, 0, 0]);
+        assert_eq!(Colormap::Grayscale.color(0.5), [128, 128, 128]);
+        assert_eq!(Colormap::Grayscale.color(1.0), [255, 255, 255]);
+    }
+
+    #[test]
+    fn colorize_linear() {
+        let colorizer = DepthColorizer::new(Colormap::Grayscale).with_range(1.0, 3.0);
+        let image = colorizer.colorize(&[1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+        let values: Vec<_> = image.pixels().map(|pixel| pixel[0]).collect();
+        assert_eq!(values, vec![0, 128, 255, 255]);
+    }
+
+    #[test]
+    fn colorize_histogram_equalization_is_monotonic() {
+        let distances = [0.5, 0.51, 0.52, 0.53, 0.6, 2.0, 5.0, 5.5];
+        let colorizer = DepthColorizer::new(Colormap::Grayscale).with_histogram_equalization(true);
+        let image = colorizer.colorize(&distances, 4, 2).unwrap();
+        let values: Vec<_> = image.pixels().map(|pixel| pixel[0]).collect();
+
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(*values.last().unwrap(), 255);
+        // clustered distances are spread over the palette
+        assert!(values[3] >= 127);
+    }
+
+    #[test]
+    fn colorize_histogram_equalization_ignores_zero_depth() {
+        let distances = [0.0, 0.0, 0.0, f32::NAN, 1.0, 2.0];
+        let colorizer = DepthColorizer::new(Colormap::Turbo).with_histogram_equalization(true);
+        let image = colorizer.colorize(&distances, 3, 2).unwrap();
+        let pixels: Vec<_> = image.pixels().map(|pixel| pixel.0).collect();
+
+        assert!(pixels[..4].iter().all(|&pixel| pixel == [0, 0, 0]));
+        assert_eq!(pixels[4], Colormap::Turbo.color(0.5));
+        assert_eq!(pixels[5], Colormap::Turbo.color(1.0));
+    }
+
+    #[test]
+    fn colorize_all_zero_depth() {
+        let colorizer = DepthColorizer::new(Colormap::Turbo).with_histogram_equalization(true);
+        let image = colorizer.colorize(&[0.0; 4], 2, 2).unwrap();
+        assert!(image.pixels().all(|pixel| pixel.0 == [0, 0, 0]));
+    }
+
+    #[test]
+    fn colorize_invalid_input() {
+        let colorizer = DepthColorizer::new(Colormap::Turbo);
+        assert!(colorizer.colorize(&[1.0; 3], 2, 2).is_err());
+        let colorizer = colorizer.with_range(2.0, 1.0);
+        assert!(colorizer.colorize(&[1.0; 4], 2, 2).is_err());
+    }
+}
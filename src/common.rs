@@ -3,12 +3,14 @@ pub use crate::kind::{Format, StreamKind};
 pub use image::{
     buffer::ConvertBuffer,
     flat::{FlatSamples, SampleLayout},
-    Bgr, Bgra, ColorType, DynamicImage, ImageBuffer, Luma, Rgb, Rgba,
+    Bgr, Bgra, DynamicImage, ImageBuffer, Luma, Pixel, Rgb, Rgba,
 };
 #[cfg(feature = "with-nalgebra")]
 pub use nalgebra::{
     Isometry3, MatrixMN, Quaternion, Translation3, Unit, UnitQuaternion, Vector3, U3,
 };
+#[cfg(feature = "with-ndarray")]
+pub use ndarray::{ArrayView2, ArrayView3, ShapeBuilder};
 pub use num_derive::FromPrimitive;
 pub use num_traits::FromPrimitive;
 pub use realsense_sys as sys;
@@ -20,20 +22,28 @@ pub use std::os::windows::ffi::OsStrExt;
 pub use std::{
     borrow::{Borrow, Cow},
     collections::HashMap,
-    convert::{AsMut, AsRef},
+    convert::{AsMut, AsRef, TryFrom},
     error::Error as StdError,
     ffi::{CStr, CString},
     fmt::{Debug, Display, Formatter, Result as FormatResult},
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
     iter::FusedIterator,
     marker::PhantomData,
     mem::{self, MaybeUninit},
     num::NonZeroU8,
     ops::{Deref, DerefMut},
     os::raw::{c_int, c_uchar, c_uint, c_void},
+    panic,
     path::Path,
+    pin::Pin,
     ptr::{self, NonNull},
     result, slice,
-    sync::atomic::{AtomicPtr, Ordering},
+    sync::{
+        atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context as TaskContext, Poll},
     thread,
     time::Duration,
 };
@@ -146,7 +146,7 @@ impl Config {
             let mut checker = ErrorChecker::new();
             let ptr = sys::rs2_config_resolve(
                 self.ptr.as_ptr(),
-                pipeline.ptr.as_ptr(),
+                pipeline.ptr().as_ptr(),
                 checker.inner_mut_ptr(),
             );
             checker.check()?;
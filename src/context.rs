@@ -118,3 +118,9 @@ impl Drop for Context {
 }
 
 unsafe impl Send for Context {}
+
+// SAFETY: The shared methods are `query_devices` and `create_device_hub`. Device queries
+// enumerate the backend into a new list without mutating `rs2_context`, and the device hub
+// registers its devices-changed callback to the context, whose callback list is guarded by
+// the internal mutex of librealsense context. `add_device` mutates the context and takes &mut self.
+unsafe impl Sync for Context {}
@@ -0,0 +1,382 @@
+//! Pure-Rust decoders for packed frame formats.
+//!
+//! The functions work on raw frame bytes, such as those returned by
+//! [GenericFrameEx::data](crate::frame::GenericFrameEx::data), so that frames in
+//! low-bandwidth formats can be decoded on any thread without librealsense processing blocks.
+//!
+//! Samples with less than 16 bits are scaled to the full `u16` range.
+
+use crate::{
+    common::*,
+    error::{Error, Result},
+};
+
+/// 8-bit RGB image with owned buffer.
+pub type RgbImage = ImageBuffer<Rgb<u8>, Vec<u8>>;
+/// 8-bit grayscale image with owned buffer.
+pub type GrayImage = ImageBuffer<Luma<u8>, Vec<u8>>;
+/// 16-bit grayscale image with owned buffer.
+pub type Gray16Image = ImageBuffer<Luma<u16>, Vec<u16>>;
+
+/// The image type returned by [decode].
+#[derive(Debug, Clone)]
+pub enum DecodedImage {
+    Rgb8(RgbImage),
+    Luma16(Gray16Image),
+    StereoLuma8 {
+        left: GrayImage,
+        right: GrayImage,
+    },
+    StereoLuma16 {
+        left: Gray16Image,
+        right: Gray16Image,
+    },
+}
+
+/// Decodes raw frame data of given format.
+///
+/// It supports [Yuyv](Format::Yuyv), [Uyvy](Format::Uyvy), [Y8I](Format::Y8I),
+/// [Y12I](Format::Y12I), [Y10Bpack](Format::Y10Bpack) and [Raw10](Format::Raw10) formats.
+/// MJPEG data is handled by `mjpeg_to_image` instead, which requires the **with-mjpeg** feature.
+pub fn decode(
+    format: Format,
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride_in_bytes: usize,
+) -> Result<DecodedImage> {
+    let image = match format {
+        Format::Yuyv => DecodedImage::Rgb8(yuyv_to_rgb(data, width, height, stride_in_bytes)?),
+        Format::Uyvy => DecodedImage::Rgb8(uyvy_to_rgb(data, width, height, stride_in_bytes)?),
+        Format::Y8I => {
+            let (left, right) = y8i_to_luma(data, width, height, stride_in_bytes)?;
+            DecodedImage::StereoLuma8 { left, right }
+        }
+        Format::Y12I => {
+            let (left, right) = y12i_to_luma(data, width, height, stride_in_bytes)?;
+            DecodedImage::StereoLuma16 { left, right }
+        }
+        Format::Y10Bpack => {
+            DecodedImage::Luma16(y10bpack_to_luma(data, width, height, stride_in_bytes)?)
+        }
+        Format::Raw10 => DecodedImage::Luma16(raw10_to_luma(data, width, height, stride_in_bytes)?),
+        _ => return Err(Error::UnsupportedFormat(format)),
+    };
+    Ok(image)
+}
+
+/// Decompresses the JPEG data of an MJPEG frame.
+///
+/// The compressed data can be obtained by [VideoFrameEx::jpeg_bytes](crate::frame::VideoFrameEx::jpeg_bytes).
+#[cfg(feature = "with-mjpeg")]
+pub fn mjpeg_to_image(data: &[u8]) -> Result<DynamicImage> {
+    image::load_from_memory_with_format(data, image::ImageFormat::Jpeg)
+        .map_err(|err| Error::InvalidData(format!("failed to decode JPEG data: {}", err)))
+}
+
+/// Converts YUYV (YUY2) data to RGB.
+pub fn yuyv_to_rgb(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride_in_bytes: usize,
+) -> Result<RgbImage> {
+    decode_yuv422(data, width, height, stride_in_bytes, [0, 1, 2, 3])
+}
+
+/// Converts UYVY data to RGB.
+pub fn uyvy_to_rgb(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride_in_bytes: usize,
+) -> Result<RgbImage> {
+    decode_yuv422(data, width, height, stride_in_bytes, [1, 0, 3, 2])
+}
+
+/// Splits interleaved 8-bit stereo data into left and right images.
+pub fn y8i_to_luma(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride_in_bytes: usize,
+) -> Result<(GrayImage, GrayImage)> {
+    let rows = rows(data, width * 2, height, stride_in_bytes)?;
+    let mut left = Vec::with_capacity(width * height);
+    let mut right = Vec::with_capacity(width * height);
+
+    for row in rows {
+        for pixel in row.chunks_exact(2) {
+            left.push(pixel[0]);
+            right.push(pixel[1]);
+        }
+    }
+
+    Ok((
+        into_image(left, width, height),
+        into_image(right, width, height),
+    ))
+}
+
+/// Splits interleaved 12-bit stereo data into left and right images.
+pub fn y12i_to_luma(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride_in_bytes: usize,
+) -> Result<(Gray16Image, Gray16Image)> {
+    let rows = rows(data, width * 3, height, stride_in_bytes)?;
+    let mut left = Vec::with_capacity(width * height);
+    let mut right = Vec::with_capacity(width * height);
+
+    for row in rows {
+        for pixel in row.chunks_exact(3) {
+            // 24-bit little-endian word, with the right sample in the low 12 bits
+            let right_value = (pixel[1] as u16 & 0x0f) << 8 | pixel[0] as u16;
+            let left_value = (pixel[2] as u16) << 4 | (pixel[1] as u16) >> 4;
+            left.push(scale_12bit(left_value));
+            right.push(scale_12bit(right_value));
+        }
+    }
+
+    Ok((
+        into_image(left, width, height),
+        into_image(right, width, height),
+    ))
+}
+
+/// Unpacks 10-bit grayscale data stored in Y10BPACK layout.
+pub fn y10bpack_to_luma(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride_in_bytes: usize,
+) -> Result<Gray16Image> {
+    unpack_10bit(data, width, height, stride_in_bytes)
+}
+
+/// Unpacks 10-bit raw sensor data stored in MIPI RAW10 layout.
+///
+/// The Bayer mosaic is kept as is.
+pub fn raw10_to_luma(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride_in_bytes: usize,
+) -> Result<Gray16Image> {
+    unpack_10bit(data, width, height, stride_in_bytes)
+}
+
+/// Decodes 4:2:2 data, where `order` gives the byte offsets of Y0, U, Y1 and V in each macropixel.
+fn decode_yuv422(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride_in_bytes: usize,
+    order: [usize; 4],
+) -> Result<RgbImage> {
+    if width & 0b1 != 0 {
+        return Err(Error::InvalidData(format!(
+            "4:2:2 image width must be even, but got {}",
+            width
+        )));
+    }
+
+    let rows = rows(data, width * 2, height, stride_in_bytes)?;
+    let [y0_index, u_index, y1_index, v_index] = order;
+    let mut samples = Vec::with_capacity(width * height * 3);
+
+    for row in rows {
+        for macropixel in row.chunks_exact(4) {
+            let u = macropixel[u_index];
+            let v = macropixel[v_index];
+            samples.extend_from_slice(&yuv_to_rgb(macropixel[y0_index], u, v));
+            samples.extend_from_slice(&yuv_to_rgb(macropixel[y1_index], u, v));
+        }
+    }
+
+    Ok(into_image(samples, width, height))
+}
+
+/// Unpacks 4 pixels from every 5 bytes, where the first 4 bytes hold the 8 most significant
+/// bits of each pixel and the last byte holds the 2 least significant bits.
+fn unpack_10bit(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride_in_bytes: usize,
+) -> Result<Gray16Image> {
+    if width & 0b11 != 0 {
+        return Err(Error::InvalidData(format!(
+            "10-bit packed image width must be a multiple of 4, but got {}",
+            width
+        )));
+    }
+
+    let rows = rows(data, width / 4 * 5, height, stride_in_bytes)?;
+    let mut samples = Vec::with_capacity(width * height);
+
+    for row in rows {
+        for group in row.chunks_exact(5) {
+            let lsbs = group[4] as u16;
+            for (index, &msb) in group[..4].iter().enumerate() {
+                let value = (msb as u16) << 2 | (lsbs >> (index * 2)) & 0b11;
+                samples.push(scale_10bit(value));
+            }
+        }
+    }
+
+    Ok(into_image(samples, width, height))
+}
+
+/// Converts BT.601 limited-range YUV to RGB, the same way as librealsense does.
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let c = y as i32 - 16;
+    let d = u as i32 - 128;
+    let e = v as i32 - 128;
+    let clamp = |value: i32| value.clamp(0, 255) as u8;
+
+    [
+        clamp((298 * c + 409 * e + 128) >> 8),
+        clamp((298 * c - 100 * d - 208 * e + 128) >> 8),
+        clamp((298 * c + 516 * d + 128) >> 8),
+    ]
+}
+
+fn scale_10bit(value: u16) -> u16 {
+    value << 6 | value >> 4
+}
+
+fn scale_12bit(value: u16) -> u16 {
+    value << 4 | value >> 8
+}
+
+/// Splits data into rows of `row_size` bytes, skipping the row padding.
+fn rows(
+    data: &[u8],
+    row_size: usize,
+    height: usize,
+    stride_in_bytes: usize,
+) -> Result<impl Iterator<Item = &[u8]>> {
+    if stride_in_bytes < row_size {
+        return Err(Error::InvalidData(format!(
+            "stride {} is smaller than row size {}",
+            stride_in_bytes, row_size
+        )));
+    }
+
+    let expected_size = match height {
+        0 => 0,
+        _ => stride_in_bytes * (height - 1) + row_size,
+    };
+    if data.len() < expected_size {
+        return Err(Error::InvalidData(format!(
+            "expect at least {} bytes, but got {} bytes",
+            expected_size,
+            data.len()
+        )));
+    }
+
+    let rows = (0..height).map(move |row| {
+        let begin = row * stride_in_bytes;
+        &data[begin..begin + row_size]
+    });
+    Ok(rows)
+}
+
+fn into_image<P>(
+    samples: Vec<P::Subpixel>,
+    width: usize,
+    height: usize,
+) -> ImageBuffer<P, Vec<P::Subpixel>>
+where
+    P: Pixel + 'static,
+{
+    ImageBuffer::from_raw(width as u32, height as u32, samples).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yuyv_known_pixels() {
+        // black, white, then red and its chroma neighbor in BT.601 limited range
+        let data = [16, 128, 235, 128, 81, 90, 81, 240];
+        let image = yuyv_to_rgb(&data, 4, 1, 8).unwrap();
+        assert_eq!(
+            image.into_raw(),
+            vec![0, 0, 0, 255, 255, 255, 255, 0, 0, 255, 0, 0]
+        );
+    }
+
+    #[test]
+    fn uyvy_known_pixels() {
+        let data = [128, 16, 128, 235, 90, 81, 240, 81];
+        let image = uyvy_to_rgb(&data, 4, 1, 8).unwrap();
+        assert_eq!(
+            image.into_raw(),
+            vec![0, 0, 0, 255, 255, 255, 255, 0, 0, 255, 0, 0]
+        );
+    }
+
+    #[test]
+    fn yuv422_skips_row_padding() {
+        let data = [16, 128, 16, 128, 0xee, 0xee, 235, 128, 235, 128];
+        let image = yuyv_to_rgb(&data, 2, 2, 6).unwrap();
+        assert_eq!(
+            image.into_raw(),
+            vec![0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255]
+        );
+    }
+
+    #[test]
+    fn yuv422_rejects_odd_width() {
+        assert!(yuyv_to_rgb(&[0; 6], 3, 1, 6).is_err());
+    }
+
+    #[test]
+    fn y8i_splits_channels() {
+        let data = [1, 2, 3, 4, 0xee, 5, 6, 7, 8, 0xee];
+        let (left, right) = y8i_to_luma(&data, 2, 2, 5).unwrap();
+        assert_eq!(left.into_raw(), vec![1, 3, 5, 7]);
+        assert_eq!(right.into_raw(), vec![2, 4, 6, 8]);
+    }
+
+    #[test]
+    fn y12i_splits_channels() {
+        // left 0xabc and right 0x123 packed in a 24-bit little-endian word
+        let data = [0x23, 0xc1, 0xab, 0xff, 0x0f, 0x00];
+        let (left, right) = y12i_to_luma(&data, 2, 1, 6).unwrap();
+        assert_eq!(left.into_raw(), vec![0xabca, 0x0000]);
+        assert_eq!(right.into_raw(), vec![0x1231, 0xffff]);
+    }
+
+    #[test]
+    fn y10bpack_unpacks_groups() {
+        // 0x3ff, 0x000, 0x155 and 0x2aa
+        let data = [0xff, 0x00, 0x55, 0xaa, 0b10_01_00_11];
+        let image = y10bpack_to_luma(&data, 4, 1, 5).unwrap();
+        assert_eq!(image.into_raw(), vec![0xffff, 0x0000, 0x5555, 0xaaaa]);
+    }
+
+    #[test]
+    fn raw10_unpacks_groups() {
+        // 0x001, 0x100, 0x3fc and 0x003, with a padded stride
+        let data = [0x00, 0x40, 0xff, 0x00, 0b11_00_00_01, 0xee, 0xee];
+        let image = raw10_to_luma(&data, 4, 1, 7).unwrap();
+        assert_eq!(image.into_raw(), vec![0x0040, 0x4010, 0xff3f, 0x00c0]);
+    }
+
+    #[test]
+    fn unpack_10bit_rejects_bad_layout() {
+        assert!(raw10_to_luma(&[0; 5], 3, 1, 5).is_err());
+        assert!(raw10_to_luma(&[0; 5], 4, 1, 4).is_err());
+        assert!(raw10_to_luma(&[0; 9], 4, 2, 5).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_unsupported_format() {
+        assert!(decode(Format::Z16, &[0; 4], 2, 1, 4).is_err());
+    }
+}
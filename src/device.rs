@@ -183,3 +183,13 @@ impl Drop for Device {
 }
 
 unsafe impl Send for Device {}
+
+// SAFETY: The shared methods query camera info and sensors, and reset the hardware.
+// Camera info is a string map filled when librealsense creates the device and never changes
+// afterwards, so the returned `&str`s stay valid for the borrow. The sensors are created
+// along with the device, and `rs2_query_sensors` only copies them to a new list.
+// `hardware_reset` sends a firmware command through the device's hardware monitor, which
+// serializes its transfers by a mutex, so it is safe to race with the queries above. The
+// reset disconnects the device, but `rs2_device` keeps its own reference to the device
+// object, so later calls never touch freed memory.
+unsafe impl Sync for Device {}
@@ -1,6 +1,6 @@
 //! Defines the error type used by the crate.
 
-use crate::common::*;
+use crate::{common::*, options::OptionRange};
 
 #[derive(Debug)]
 pub(crate) struct ErrorChecker {
@@ -50,6 +50,14 @@ impl Drop for ErrorChecker {
 /// The error type wraps around underlying error thrown by librealsense library.
 pub enum Error {
     ToCStrConversion(&'static str),
+    Io(io::Error),
+    InvalidData(String),
+    UnsupportedFormat(Format),
+    OptionOutOfRange {
+        option: String,
+        value: f32,
+        range: OptionRange,
+    },
     Timeout(NonNull<sys::rs2_error>),
     UnsupportedOption(NonNull<sys::rs2_error>),
     Other(NonNull<sys::rs2_error>),
@@ -60,6 +68,10 @@ impl Error {
         match (self, self.ptr()) {
             (_, Some(ptr)) => get_error_message(ptr),
             (Self::ToCStrConversion(reason), None) => reason,
+            (Self::Io(_), None) => "I/O error",
+            (Self::InvalidData(reason), None) => reason,
+            (Self::UnsupportedFormat(_), None) => "unsupported format",
+            (Self::OptionOutOfRange { .. }, None) => "option value out of range",
             _ => unreachable!(),
         }
     }
@@ -72,7 +84,11 @@ impl Error {
 
     pub(crate) fn ptr(&self) -> Option<NonNull<sys::rs2_error>> {
         let ptr = match *self {
-            Error::ToCStrConversion(_reason) => return None,
+            Error::ToCStrConversion(_)
+            | Error::Io(_)
+            | Error::InvalidData(_)
+            | Error::UnsupportedFormat(_)
+            | Error::OptionOutOfRange { .. } => return None,
             Error::Timeout(ptr) => ptr,
             Error::UnsupportedOption(ptr) => ptr,
             Error::Other(ptr) => ptr,
@@ -83,19 +99,48 @@ impl Error {
 
 impl Display for Error {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> FormatResult {
-        let message = self.error_message();
-        write!(formatter, "RealSense error: {}", message)
+        match self {
+            Self::Io(err) => write!(formatter, "I/O error: {}", err),
+            Self::UnsupportedFormat(format) => {
+                write!(
+                    formatter,
+                    "RealSense error: unsupported format {:?}",
+                    format
+                )
+            }
+            Self::OptionOutOfRange {
+                option,
+                value,
+                range,
+            } => {
+                write!(
+                    formatter,
+                    "RealSense error: value {} of option {} is out of range [{}, {}] with step {}",
+                    value, option, range.min, range.max, range.step
+                )
+            }
+            _ => {
+                let message = self.error_message();
+                write!(formatter, "RealSense error: {}", message)
+            }
+        }
     }
 }
 
 impl Debug for Error {
     fn fmt(&self, formatter: &mut Formatter<'_>) -> FormatResult {
-        let message = self.error_message();
-        write!(formatter, "RealSense error: {}", message)
+        Display::fmt(self, formatter)
     }
 }
 
-impl StdError for Error {}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 unsafe impl Send for Error {}
 
@@ -0,0 +1,164 @@
+//! Defines the ordered chain of processing blocks.
+
+use crate::{
+    base::DEFAULT_TIMEOUT,
+    common::*,
+    error::{Error, Result},
+    frame::{AnyFrame, CompositeFrame, DepthFrame, Frame},
+    frame_kind::{self, FrameKind},
+    processing_block::{AnyProcessingBlock, ProcessingBlock},
+    processing_block_kind::ProcessingBlockKind,
+    processing_block_list::ProcessingBlockList,
+    sensor::Sensor,
+    sensor_kind::SensorKind,
+};
+
+/// An ordered list of processing blocks applied one after another.
+///
+/// It is typically used for depth post-processing, for example, decimation,
+/// depth to disparity, spatial and temporal filtering, disparity back to depth,
+/// and hole filling.
+///
+/// Each block waits for its output at most the [timeout](FilterChain::set_timeout),
+/// which is [DEFAULT_TIMEOUT] by default, so that blocks emitting nothing for
+/// an input do not stall the chain.
+#[derive(Debug)]
+pub struct FilterChain {
+    blocks: Vec<AnyProcessingBlock>,
+    timeout: Duration,
+}
+
+impl FilterChain {
+    /// Creates an empty chain.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum time to wait for the output of each block.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.set_timeout(timeout);
+        self
+    }
+
+    /// Sets the maximum time to wait for the output of each block.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Gets the maximum time to wait for the output of each block.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Creates a chain from the recommended processing blocks of a sensor.
+    pub fn from_sensor<Kind>(sensor: &Sensor<Kind>) -> Result<Self>
+    where
+        Kind: SensorKind,
+    {
+        Self::from_processing_block_list(sensor.recommended_processing_blocks()?)
+    }
+
+    /// Creates a chain from a list of processing blocks in the list order.
+    pub fn from_processing_block_list(list: ProcessingBlockList) -> Result<Self> {
+        let blocks = list.try_into_iter()?.collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            blocks,
+            ..Self::default()
+        })
+    }
+
+    /// Appends a processing block to the end of chain.
+    pub fn with<Kind>(mut self, block: ProcessingBlock<Kind>) -> Self
+    where
+        Kind: ProcessingBlockKind,
+    {
+        self.push(block);
+        self
+    }
+
+    /// Appends a processing block to the end of chain.
+    pub fn push<Kind>(&mut self, block: ProcessingBlock<Kind>)
+    where
+        Kind: ProcessingBlockKind,
+    {
+        self.blocks.push(block.into_any());
+    }
+
+    /// Gets the number of processing blocks.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Checks if the chain has no processing blocks.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Gets the processing blocks in chain order.
+    pub fn blocks(&self) -> &[AnyProcessingBlock] {
+        &self.blocks
+    }
+
+    /// Gets the mutable processing blocks in chain order, which is useful to change options.
+    pub fn blocks_mut(&mut self) -> &mut [AnyProcessingBlock] {
+        &mut self.blocks
+    }
+
+    /// Passes a frame through all processing blocks.
+    ///
+    /// It returns [Error::Timeout] if any block emits nothing within the timeout.
+    pub fn process<Kind>(&mut self, frame: Frame<Kind>) -> Result<AnyFrame>
+    where
+        Kind: FrameKind,
+    {
+        let mut frame = frame.into_any();
+        for block in self.blocks.iter_mut() {
+            frame = block.process_timeout(frame, self.timeout)?;
+        }
+        Ok(frame)
+    }
+
+    /// Passes a depth frame through all processing blocks.
+    ///
+    /// The output may not be a [DepthFrame] if the chain ends with a depth-to-disparity
+    /// transform or a colorizer, and thus it is returned as [AnyFrame].
+    pub fn process_depth(&mut self, frame: DepthFrame) -> Result<AnyFrame> {
+        self.process(frame)
+    }
+
+    /// Passes a composite frame through all processing blocks.
+    ///
+    /// The built-in filters process the frames of the streams they apply to, such as
+    /// depth frames, and keep other frames in the composite frame. Custom processing
+    /// blocks receive the composite frame as a whole.
+    pub fn process_composite(&mut self, frame: CompositeFrame) -> Result<CompositeFrame> {
+        self.process(frame)?
+            .try_extend_to::<frame_kind::Composite>()?
+            .map_err(|_| {
+                Error::InvalidData("the processing blocks do not output a composite frame".into())
+            })
+    }
+
+    /// Passes a frame through all processing blocks asynchronously.
+    ///
+    /// It is analogous to [process](FilterChain::process).
+    pub async fn process_async<Kind>(&mut self, frame: Frame<Kind>) -> Result<AnyFrame>
+    where
+        Kind: FrameKind,
+    {
+        let mut frame = frame.into_any();
+        for block in self.blocks.iter_mut() {
+            frame = block.process_timeout_async(frame, self.timeout).await?;
+        }
+        Ok(frame)
+    }
+}
+
+impl Default for FilterChain {
+    fn default() -> Self {
+        Self {
+            blocks: vec![],
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
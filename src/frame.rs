@@ -3,15 +3,21 @@
 #[cfg(feature = "with-image")]
 use crate::base::Rs2Image;
 use crate::{
-    base::{PoseData, Resolution, StreamProfileData},
+    base::{FrameMetadata, PoseData, Rect, RegionStats, Resolution, StreamProfileData},
     common::*,
-    error::{ErrorChecker, Result},
+    error::{Error, ErrorChecker, Result},
     frame_kind,
-    kind::{Format, FrameMetaDataValue, StreamKind, TimestampDomain},
-    sensor::{AnySensor, DepthSensor},
+    kind::{EmitterMode, Format, FrameMetaDataValue, LaserPowerMode, StreamKind, TimestampDomain},
+    sensor::AnySensor,
     stream_profile::{AnyStreamProfile, StreamProfile},
+    stream_profile_kind,
 };
 
+/// The number of [Frame] handles alive in the process.
+static FRAME_HANDLES: AtomicUsize = AtomicUsize::new(0);
+/// The number of alive [Frame] handles marked by [Frame::keep].
+static KEPT_FRAME_HANDLES: AtomicUsize = AtomicUsize::new(0);
+
 /// The trait provides common methods on frames of all kinds.
 pub trait GenericFrameEx
 where
@@ -31,6 +37,87 @@ where
         }
     }
 
+    /// Checks if the metadata is supported by the frame.
+    fn supports_metadata(&self, kind: FrameMetaDataValue) -> Result<bool> {
+        unsafe {
+            let mut checker = ErrorChecker::new();
+            let val = sys::rs2_supports_frame_metadata(
+                self.ptr().as_ptr(),
+                kind as sys::rs2_frame_metadata_value,
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+            Ok(val != 0)
+        }
+    }
+
+    /// Obtains all supported metadata of frame.
+    fn all_metadata(&self) -> Result<FrameMetadata> {
+        use FrameMetaDataValue as M;
+
+        let get = |kind| -> Result<Option<i64>> {
+            if self.supports_metadata(kind)? {
+                Ok(Some(self.metadata(kind)? as i64))
+            } else {
+                Ok(None)
+            }
+        };
+        let get_u64 = |kind| -> Result<Option<u64>> { Ok(get(kind)?.map(|val| val as u64)) };
+        let get_bool = |kind| -> Result<Option<bool>> { Ok(get(kind)?.map(|val| val != 0)) };
+
+        let exposure_roi = match (
+            get_u64(M::ExposureRoiLeft)?,
+            get_u64(M::ExposureRoiRight)?,
+            get_u64(M::ExposureRoiTop)?,
+            get_u64(M::ExposureRoiBottom)?,
+        ) {
+            // the bounds are inclusive
+            (Some(left), Some(right), Some(top), Some(bottom))
+                if left <= right && top <= bottom =>
+            {
+                Some(Rect {
+                    x: left as usize,
+                    y: top as usize,
+                    width: (right - left + 1) as usize,
+                    height: (bottom - top + 1) as usize,
+                })
+            }
+            _ => None,
+        };
+
+        let metadata = FrameMetadata {
+            frame_counter: get_u64(M::FrameCounter)?,
+            frame_timestamp: get_u64(M::FrameTimestamp)?,
+            sensor_timestamp: get_u64(M::SensorTimestamp)?,
+            actual_exposure: get_u64(M::ActualExposure)?.map(Duration::from_micros),
+            gain_level: get_u64(M::GainLevel)?,
+            auto_exposure: get_bool(M::AutoExposure)?,
+            white_balance: get_u64(M::WhiteBalance)?,
+            time_of_arrival: get_u64(M::TimeOfArrival)?,
+            temperature: get(M::Temperature)?.map(|val| val as f32),
+            backend_timestamp: get_u64(M::BackendTimestamp)?,
+            actual_fps: get_u64(M::ActualFps)?,
+            laser_power: get_u64(M::FrameLaserPower)?,
+            laser_power_mode: get_u64(M::FrameLaserPowerMode)?.and_then(LaserPowerMode::from_u64),
+            exposure_priority: get_bool(M::ExposurePriority)?,
+            exposure_roi,
+            brightness: get(M::Brightness)?,
+            contrast: get(M::Contrast)?,
+            saturation: get(M::Saturation)?,
+            sharpness: get(M::Sharpness)?,
+            auto_white_balance_temperature: get_u64(M::AutoWhiteBalanceTemperature)?,
+            backlight_compensation: get(M::BacklightCompensation)?,
+            hue: get(M::Hue)?,
+            gamma: get(M::Gamma)?,
+            manual_white_balance: get_u64(M::ManualWhiteBalance)?,
+            power_line_frequency: get_u64(M::PowerLineFrequency)?,
+            low_light_compensation: get_bool(M::LowLightCompensation)?,
+            emitter_mode: get_u64(M::FrameEmitterMode)?.and_then(EmitterMode::from_u64),
+            led_power: get_u64(M::FrameLedPower)?,
+        };
+        Ok(metadata)
+    }
+
     /// Gets frame number.
     fn number(&self) -> Result<u64> {
         unsafe {
@@ -190,7 +277,30 @@ where
         }
     }
 
+    /// Gets the compressed data of an [Mjpeg](Format::Mjpeg) frame as is.
+    ///
+    /// It is intended for recorders storing JPEG images without decoding them.
+    fn jpeg_bytes(&self) -> Result<&[u8]> {
+        let StreamProfileData { format, .. } = self.stream_profile()?.get_data()?;
+        if format != Format::Mjpeg {
+            return Err(Error::UnsupportedFormat(format));
+        }
+
+        // the frame buffer may be larger than the compressed image, so trim after the EOI marker
+        let data = self.data()?;
+        let end = data
+            .windows(2)
+            .rposition(|marker| marker == [0xff, 0xd9])
+            .map(|index| index + 2)
+            .unwrap_or_else(|| data.len());
+        Ok(&data[..end])
+    }
+
     /// Gets color image buffer referencing underlying raw data.
+    ///
+    /// It returns [Error::UnsupportedFormat](crate::error::Error::UnsupportedFormat)
+    /// if the frame format cannot be represented by [Rs2Image]. Packed formats such as
+    /// YUYV can be converted by the [decode](crate::decode) module instead.
     #[cfg(feature = "with-image")]
     fn ref_image(&self) -> Result<Rs2Image> {
         let StreamProfileData { format, .. } = self.stream_profile()?.get_data()?;
@@ -200,162 +310,104 @@ where
         debug_assert_eq!(raw_data.len() % stride_in_bytes, 0, "please report bug");
 
         let image = match format {
-            Format::Bgr8 => {
-                let channels = 3;
-
-                let sample_size = mem::size_of::<u8>();
-                debug_assert_eq!(stride_in_bytes % sample_size, 0, "please report bug");
-
-                let stride_in_samples = stride_in_bytes / sample_size;
-                debug_assert_eq!(
-                    raw_data.len(),
-                    stride_in_samples * height,
-                    "please report bug"
-                );
-                debug_assert!(width * channels <= stride_in_samples, "please report bug");
-
-                let flat = FlatSamples {
-                    samples: raw_data,
-                    layout: SampleLayout {
-                        channels: channels as u8,
-                        width: width as u32,
-                        height: height as u32,
-                        channel_stride: 1,
-                        width_stride: channels,
-                        height_stride: stride_in_samples,
-                    },
-                    color_hint: Some(ColorType::Bgr8),
-                };
-                let image = flat.try_into_buffer().unwrap();
-                Rs2Image::Bgr8(image)
-            }
-            Format::Bgra8 => {
-                let channels = 4;
-
-                let sample_size = mem::size_of::<u8>();
-                debug_assert_eq!(stride_in_bytes % sample_size, 0, "please report bug");
-
-                let stride_in_samples = stride_in_bytes / sample_size;
-                debug_assert_eq!(
-                    raw_data.len(),
-                    stride_in_samples * height,
-                    "please report bug"
-                );
-                debug_assert!(width * channels <= stride_in_samples, "please report bug");
-
-                let flat = FlatSamples {
-                    samples: raw_data,
-                    layout: SampleLayout {
-                        channels: channels as u8,
-                        width: width as u32,
-                        height: height as u32,
-                        channel_stride: 1,
-                        width_stride: channels,
-                        height_stride: stride_in_samples,
-                    },
-                    color_hint: Some(ColorType::Bgra8),
-                };
-                let image = flat.try_into_buffer().unwrap();
-                Rs2Image::Bgra8(image)
+            Format::Bgr8 => Rs2Image::Bgr8(image_from_samples(
+                raw_data,
+                width,
+                height,
+                stride_in_bytes,
+            )?),
+            Format::Bgra8 => Rs2Image::Bgra8(image_from_samples(
+                raw_data,
+                width,
+                height,
+                stride_in_bytes,
+            )?),
+            Format::Rgb8 => Rs2Image::Rgb8(image_from_samples(
+                raw_data,
+                width,
+                height,
+                stride_in_bytes,
+            )?),
+            Format::Rgba8 => Rs2Image::Rgba8(image_from_samples(
+                raw_data,
+                width,
+                height,
+                stride_in_bytes,
+            )?),
+            Format::Y8 | Format::Raw8 => Rs2Image::Luma8(image_from_samples(
+                raw_data,
+                width,
+                height,
+                stride_in_bytes,
+            )?),
+            Format::Z16 | Format::Y16 | Format::Raw16 | Format::Disparity16 => {
+                let samples: &[u16] = transmute_samples(raw_data)?;
+                Rs2Image::Luma16(image_from_samples(samples, width, height, stride_in_bytes)?)
             }
-            Format::Rgb8 => {
-                let channels = 3;
-
-                let sample_size = mem::size_of::<u8>();
-                debug_assert_eq!(stride_in_bytes % sample_size, 0, "please report bug");
-
-                let stride_in_samples = stride_in_bytes / sample_size;
-                debug_assert_eq!(
-                    raw_data.len(),
-                    stride_in_samples * height,
-                    "please report bug"
-                );
-                debug_assert!(width * channels <= stride_in_samples, "please report bug");
-
-                let flat = FlatSamples {
-                    samples: raw_data,
-                    layout: SampleLayout {
-                        channels: channels as u8,
-                        width: width as u32,
-                        height: height as u32,
-                        channel_stride: 1,
-                        width_stride: channels,
-                        height_stride: stride_in_samples,
-                    },
-                    color_hint: Some(ColorType::Rgb8),
-                };
-                let image = flat.try_into_buffer().unwrap();
-                Rs2Image::Rgb8(image)
+            Format::Disparity32 | Format::Distance => {
+                let samples: &[f32] = transmute_samples(raw_data)?;
+                Rs2Image::LumaF32(image_from_samples(samples, width, height, stride_in_bytes)?)
             }
-            Format::Rgba8 => {
-                let channels = 4;
-
-                let sample_size = mem::size_of::<u8>();
-                debug_assert_eq!(stride_in_bytes % sample_size, 0, "please report bug");
-
-                let stride_in_samples = stride_in_bytes / sample_size;
-                debug_assert_eq!(
-                    raw_data.len(),
-                    stride_in_samples * height,
-                    "please report bug"
-                );
-                debug_assert!(width * channels <= stride_in_samples, "please report bug");
-
-                let flat = FlatSamples {
-                    samples: raw_data,
-                    layout: SampleLayout {
-                        channels: channels as u8,
-                        width: width as u32,
-                        height: height as u32,
-                        channel_stride: 1,
-                        width_stride: channels,
-                        height_stride: stride_in_samples,
-                    },
-                    color_hint: Some(ColorType::Rgba8),
-                };
-                let image = flat.try_into_buffer().unwrap();
-                Rs2Image::Rgba8(image)
+            Format::Xyz32F => {
+                let samples: &[f32] = transmute_samples(raw_data)?;
+                Rs2Image::RgbF32(image_from_samples(samples, width, height, stride_in_bytes)?)
             }
-            Format::Z16 => {
-                let sample_size = mem::size_of::<u16>();
-                debug_assert_eq!(stride_in_bytes % sample_size, 0, "please report bug");
-
-                let depth_data: &[u16] =
-                    safe_transmute::transmute_many::<u16, PedanticGuard>(raw_data).unwrap();
-
-                let stride_in_samples = stride_in_bytes / sample_size;
-                debug_assert_eq!(
-                    depth_data.len(),
-                    stride_in_samples * height,
-                    "please report bug"
-                );
-                debug_assert!(width <= stride_in_samples, "please report bug");
-
-                let flat = FlatSamples {
-                    samples: depth_data,
-                    layout: SampleLayout {
-                        channels: 1,
-                        width: width as u32,
-                        height: height as u32,
-                        channel_stride: 1,
-                        width_stride: 1,
-                        height_stride: stride_in_bytes / sample_size,
-                    },
-                    color_hint: Some(ColorType::L16),
-                };
-                let image = flat.try_into_buffer().unwrap();
-                Rs2Image::Luma16(image)
-            }
-            _ => unreachable!("unsupported format. please report bug"),
+            _ => return Err(Error::UnsupportedFormat(format)),
         };
 
         Ok(image)
     }
 
     /// Builds an owned image. Note that it incurs memory copy.
+    ///
+    /// It returns error on formats with floating point samples. MJPEG frames are
+    /// decompressed if the **with-mjpeg** feature is enabled.
     #[cfg(feature = "with-image")]
     fn owned_image(&self) -> Result<DynamicImage> {
-        Ok(self.ref_image()?.into())
+        #[cfg(feature = "with-mjpeg")]
+        {
+            let StreamProfileData { format, .. } = self.stream_profile()?.get_data()?;
+            if format == Format::Mjpeg {
+                return crate::decode::mjpeg_to_image(self.jpeg_bytes()?);
+            }
+        }
+
+        self.ref_image()?.to_owned()
+    }
+
+    /// Gets a `height × width × channels` array view on 8-bit image data without copying.
+    ///
+    /// The row stride of the frame is honored. It returns error on formats with other sample types.
+    #[cfg(feature = "with-ndarray")]
+    fn color_array(&self) -> Result<ArrayView3<'_, u8>> {
+        let StreamProfileData { format, .. } = self.stream_profile()?.get_data()?;
+        let channels = match format {
+            Format::Y8 | Format::Raw8 => 1,
+            Format::Bgr8 | Format::Rgb8 => 3,
+            Format::Bgra8 | Format::Rgba8 => 4,
+            _ => return Err(Error::UnsupportedFormat(format)),
+        };
+        let Resolution { width, height } = self.resolution()?;
+        let stride_in_bytes = self.stride_in_bytes()?;
+
+        ArrayView3::from_shape(
+            (height, width, channels).strides((stride_in_bytes, channels, 1)),
+            self.data()?,
+        )
+        .map_err(|err| Error::InvalidData(format!("invalid frame layout: {}", err)))
+    }
+
+    /// Gets a `height × width` array view on distances in meters without copying.
+    ///
+    /// It works on frames in [Distance](Format::Distance) format, such as those produced by
+    /// [UnitsTransform](crate::processing_block::UnitsTransform).
+    #[cfg(feature = "with-ndarray")]
+    fn distance_array(&self) -> Result<ArrayView2<'_, f32>> {
+        let StreamProfileData { format, .. } = self.stream_profile()?.get_data()?;
+        if format != Format::Distance {
+            return Err(Error::UnsupportedFormat(format));
+        }
+        sample_array(self)
     }
 }
 
@@ -383,11 +435,128 @@ where
     }
 
     /// Gets the length in meter per distance unit.
+    ///
+    /// The value is the depth units at capture time stored in the frame, rather than the current
+    /// [DepthUnits](crate::kind::Rs2Option::DepthUnits) option of the sensor. It also works on
+    /// frames without a sensor, such as those from playback or software devices.
     fn depth_units(&self) -> Result<f32> {
-        let sensor = self.sensor()?;
-        let sensor: DepthSensor = sensor.try_extend_to()?.unwrap();
-        let depth_units = sensor.depth_units()?;
-        Ok(depth_units)
+        unsafe {
+            let mut checker = ErrorChecker::new();
+            let depth_units =
+                sys::rs2_depth_frame_get_units(self.ptr().as_ptr(), checker.inner_mut_ptr());
+            checker.check()?;
+            Ok(depth_units)
+        }
+    }
+
+    /// Converts the whole frame to distances in meters, stored in row-major order.
+    ///
+    /// Unlike calling [distance](DepthFrameEx::distance) on each pixel, the conversion is
+    /// done in Rust over the [Z16](Format::Z16) data with a single lookup of depth units.
+    fn to_meters_vec(&self) -> Result<Vec<f32>> {
+        let depth_units = self.depth_units()?;
+        let (samples, stride, Resolution { width, height }) = z16_samples(self)?;
+        let distances = samples
+            .chunks(stride)
+            .take(height)
+            .flat_map(|row| row[..width].iter().map(|&value| value as f32 * depth_units))
+            .collect();
+        Ok(distances)
+    }
+
+    /// Converts the whole frame to an image of distances in meters.
+    ///
+    /// It is analogous to [to_meters_vec](DepthFrameEx::to_meters_vec).
+    #[cfg(feature = "with-image")]
+    fn to_meters(&self) -> Result<ImageBuffer<Luma<f32>, Vec<f32>>> {
+        let Resolution { width, height } = self.resolution()?;
+        let distances = self.to_meters_vec()?;
+        Ok(ImageBuffer::from_raw(width as u32, height as u32, distances).unwrap())
+    }
+
+    /// Gets distances in meters at given `(x, y)` coordinates.
+    ///
+    /// It returns error if any of the coordinates is out of bound.
+    fn distances(&self, coordinates: &[(usize, usize)]) -> Result<Vec<f32>> {
+        let depth_units = self.depth_units()?;
+        let (samples, stride, Resolution { width, height }) = z16_samples(self)?;
+        coordinates
+            .iter()
+            .map(|&(x, y)| {
+                if x >= width || y >= height {
+                    return Err(Error::InvalidData(format!(
+                        "coordinates ({}, {}) out of bound of {}x{} image",
+                        x, y, width, height
+                    )));
+                }
+                Ok(samples[y * stride + x] as f32 * depth_units)
+            })
+            .collect()
+    }
+
+    /// Computes statistics of valid distances within a region.
+    ///
+    /// The region is clipped to the image. It returns `None` if the clipped region
+    /// contains no valid depth.
+    fn region_stats(&self, rect: Rect) -> Result<Option<RegionStats>> {
+        let depth_units = self.depth_units()?;
+        let (samples, stride, Resolution { width, height }) = z16_samples(self)?;
+        let Rect {
+            x,
+            y,
+            width: rect_width,
+            height: rect_height,
+        } = rect;
+        let x_end = x.saturating_add(rect_width).min(width);
+        let y_end = y.saturating_add(rect_height).min(height);
+        let x_begin = x.min(x_end);
+        let y_begin = y.min(y_end);
+
+        let mut values: Vec<u16> = (y_begin..y_end)
+            .flat_map(|row| {
+                let begin = row * stride;
+                samples[(begin + x_begin)..(begin + x_end)].iter().copied()
+            })
+            .filter(|&value| value != 0)
+            .collect();
+        if values.is_empty() {
+            return Ok(None);
+        }
+
+        let valid_count = values.len();
+        let sum: u64 = values.iter().map(|&value| value as u64).sum();
+        let min = *values.iter().min().unwrap();
+        let max = *values.iter().max().unwrap();
+        let median = {
+            let mid = valid_count / 2;
+            let (lower, &mut upper, _) = values.select_nth_unstable(mid);
+            if valid_count & 0b1 == 0 {
+                let lower = *lower.iter().max().unwrap();
+                (lower as f32 + upper as f32) / 2.0
+            } else {
+                upper as f32
+            }
+        };
+
+        Ok(Some(RegionStats {
+            min: min as f32 * depth_units,
+            max: max as f32 * depth_units,
+            mean: sum as f32 / valid_count as f32 * depth_units,
+            median: median * depth_units,
+            valid_count,
+        }))
+    }
+
+    /// Gets a `height × width` array view on raw [Z16](Format::Z16) depth values without copying.
+    ///
+    /// Multiply the values by [depth_units](DepthFrameEx::depth_units) to obtain distances in meters.
+    #[cfg(feature = "with-ndarray")]
+    fn depth_array(&self) -> Result<ArrayView2<'_, u16>> {
+        let StreamProfileData { format, .. } = self.stream_profile()?.get_data()?;
+        if format != Format::Z16 {
+            return Err(Error::UnsupportedFormat(format));
+        }
+        sample_array(self)
     }
 }
 
@@ -412,6 +581,95 @@ where
     }
 }
 
+/// Reinterprets frame data as a slice of samples.
+fn transmute_samples<T>(bytes: &[u8]) -> Result<&[T]>
+where
+    T: safe_transmute::TriviallyTransmutable,
+{
+    safe_transmute::transmute_many::<T, PedanticGuard>(bytes)
+        .map_err(|err| Error::InvalidData(format!("invalid sample data: {}", err)))
+}
+
+/// Wraps the samples of a video frame in an image buffer without copying.
+#[cfg(feature = "with-image")]
+fn image_from_samples<P>(
+    samples: &[P::Subpixel],
+    width: usize,
+    height: usize,
+    stride_in_bytes: usize,
+) -> Result<ImageBuffer<P, &[P::Subpixel]>>
+where
+    P: Pixel + 'static,
+    P::Subpixel: 'static,
+{
+    let channels = P::CHANNEL_COUNT as usize;
+    let sample_size = mem::size_of::<P::Subpixel>();
+    debug_assert_eq!(stride_in_bytes % sample_size, 0, "please report bug");
+
+    let stride_in_samples = stride_in_bytes / sample_size;
+    debug_assert_eq!(
+        samples.len(),
+        stride_in_samples * height,
+        "please report bug"
+    );
+    debug_assert!(width * channels <= stride_in_samples, "please report bug");
+
+    let flat = FlatSamples {
+        samples,
+        layout: SampleLayout {
+            channels: channels as u8,
+            width: width as u32,
+            height: height as u32,
+            channel_stride: 1,
+            width_stride: channels,
+            height_stride: stride_in_samples,
+        },
+        color_hint: None,
+    };
+    flat.try_into_buffer()
+        .map_err(|(err, _)| Error::InvalidData(format!("invalid image layout: {}", err)))
+}
+
+/// Views the data of a single channel video frame as a 2D array, honoring the row stride.
+#[cfg(feature = "with-ndarray")]
+fn sample_array<F, T>(frame: &F) -> Result<ArrayView2<'_, T>>
+where
+    F: VideoFrameEx,
+    T: safe_transmute::TriviallyTransmutable,
+{
+    let Resolution { width, height } = frame.resolution()?;
+    let stride_in_bytes = frame.stride_in_bytes()?;
+    let sample_size = mem::size_of::<T>();
+    debug_assert_eq!(stride_in_bytes % sample_size, 0, "please report bug");
+
+    let samples = transmute_samples::<T>(frame.data()?)?;
+    ArrayView2::from_shape(
+        (height, width).strides((stride_in_bytes / sample_size, 1)),
+        samples,
+    )
+    .map_err(|err| Error::InvalidData(format!("invalid frame layout: {}", err)))
+}
+
+/// Gets the samples of a [Z16](Format::Z16) frame along with the row stride in samples.
+fn z16_samples<F>(frame: &F) -> Result<(&[u16], usize, Resolution)>
+where
+    F: VideoFrameEx,
+{
+    let StreamProfileData { format, .. } = frame.stream_profile()?.get_data()?;
+    if format != Format::Z16 {
+        return Err(Error::UnsupportedFormat(format));
+    }
+
+    let resolution = frame.resolution()?;
+    let stride = frame.stride_in_bytes()? / mem::size_of::<u16>();
+    let samples = transmute_samples::<u16>(frame.data()?)?;
+    debug_assert!(
+        samples.len() >= stride * resolution.height,
+        "please report bug"
+    );
+    Ok((samples, stride, resolution))
+}
+
 /// The type returned by [Frame::<Any>::try_extend](Frame::try_extend).
 ///
 /// It enumerates all possible frame extensions. If the frame failed to
@@ -444,6 +702,7 @@ where
     Kind: frame_kind::FrameKind,
 {
     pub(crate) ptr: NonNull<sys::rs2_frame>,
+    kept: AtomicBool,
     _phantom: PhantomData<Kind>,
 }
 
@@ -451,19 +710,36 @@ impl<Kind> GenericFrameEx for Frame<Kind>
 where
     Kind: frame_kind::FrameKind,
 {
+    fn try_clone(&self) -> Result<Self> {
+        let frame = unsafe {
+            // add reference
+            let mut checker = ErrorChecker::new();
+            sys::rs2_frame_add_ref(self.ptr.as_ptr(), checker.inner_mut_ptr());
+            checker.check()?;
+            Self::from_raw(self.ptr.as_ptr())
+        };
+        if self.is_kept() {
+            frame.mark_kept();
+        }
+        Ok(frame)
+    }
+
     fn ptr(&self) -> NonNull<sys::rs2_frame> {
         self.ptr
     }
 
     fn into_raw(self) -> *mut sys::rs2_frame {
         let ptr = self.ptr;
+        self.release_handle();
         mem::forget(self);
         ptr.as_ptr()
     }
 
     unsafe fn from_raw(ptr: *mut sys::rs2_frame) -> Self {
+        FRAME_HANDLES.fetch_add(1, Ordering::SeqCst);
         Self {
             ptr: NonNull::new(ptr).unwrap(),
+            kept: AtomicBool::new(false),
             _phantom: PhantomData,
         }
     }
@@ -481,7 +757,60 @@ impl DepthFrameEx for DisparityFrame {}
 
 impl DisparityFrameEx for DisparityFrame {}
 
-impl<Kind> Frame<Kind> where Kind: frame_kind::FrameKind {}
+impl<Kind> Frame<Kind>
+where
+    Kind: frame_kind::FrameKind,
+{
+    /// Erases the frame kind.
+    pub fn into_any(self) -> AnyFrame {
+        self.retag()
+    }
+
+    /// Detaches the frame from the frame pool of librealsense.
+    ///
+    /// Frames are allocated from a fixed-size pool per stream, and holding them for
+    /// longer than a few frame periods causes frame drops. Kept frames no longer count
+    /// against the pool, at the cost of memory allocations. It is also applied to
+    /// sub-frames of a composite frame.
+    pub fn keep(&self) {
+        unsafe {
+            sys::rs2_keep_frame(self.ptr.as_ptr());
+        }
+        self.mark_kept();
+    }
+
+    /// Checks if [keep](Frame::keep) is called on this handle or the handle it is cloned from.
+    pub fn is_kept(&self) -> bool {
+        self.kept.load(Ordering::SeqCst)
+    }
+
+    fn mark_kept(&self) {
+        if !self.kept.swap(true, Ordering::SeqCst) {
+            KEPT_FRAME_HANDLES.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Changes the frame kind without touching the handle counts.
+    fn retag<NewKind>(self) -> Frame<NewKind>
+    where
+        NewKind: frame_kind::FrameKind,
+    {
+        let frame = Frame {
+            ptr: self.ptr,
+            kept: AtomicBool::new(self.is_kept()),
+            _phantom: PhantomData,
+        };
+        mem::forget(self);
+        frame
+    }
+
+    fn release_handle(&self) {
+        FRAME_HANDLES.fetch_sub(1, Ordering::SeqCst);
+        if self.is_kept() {
+            KEPT_FRAME_HANDLES.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
 
 impl AnyFrame {
     pub fn is_extendable_to<Kind>(&self) -> Result<bool>
@@ -506,12 +835,7 @@ impl AnyFrame {
     {
         let is_extendable = self.is_extendable_to::<Kind>()?;
         if is_extendable {
-            let ptr = self.into_raw();
-            let frame = Frame {
-                ptr: NonNull::new(ptr).unwrap(),
-                _phantom: PhantomData,
-            };
-            Ok(Ok(frame))
+            Ok(Ok(self.retag()))
         } else {
             Ok(Err(self))
         }
@@ -624,11 +948,39 @@ impl CompositeFrame {
     pub fn first_of<Kind>(&self, stream: StreamKind) -> Result<Option<Frame<Kind>>>
     where
         Kind: frame_kind::NonAnyFrameKind,
+    {
+        self.first_matching(|data| data.stream == stream)
+    }
+
+    /// Finds the frame of given stream kind, stream index and format.
+    ///
+    /// Streams that come in a single instance, such as color and depth, have index 0.
+    pub fn find(
+        &self,
+        stream: StreamKind,
+        index: usize,
+        format: Format,
+    ) -> Result<Option<AnyFrame>> {
+        for result in self.try_iter()? {
+            let frame = result?;
+            let data = frame.stream_profile()?.get_data()?;
+            if data.stream == stream && data.index == index && data.format == format {
+                return Ok(Some(frame));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Finds the first frame of type `Kind` whose stream profile satisfies the predicate.
+    fn first_matching<Kind, P>(&self, mut predicate: P) -> Result<Option<Frame<Kind>>>
+    where
+        Kind: frame_kind::NonAnyFrameKind,
+        P: FnMut(&StreamProfileData) -> bool,
     {
         for result in self.try_iter()? {
             let frame_any = result?;
             if let Ok(frame) = frame_any.try_extend_to::<Kind>()? {
-                if frame.stream_profile()?.get_data()?.stream == stream {
+                if predicate(&frame.stream_profile()?.get_data()?) {
                     return Ok(Some(frame));
                 }
             }
@@ -647,6 +999,39 @@ impl CompositeFrame {
     pub fn pose_frame(&self) -> Result<Option<PoseFrame>> {
         self.first_of::<frame_kind::Pose>(StreamKind::Pose)
     }
+
+    /// Gets the infrared frame by stream index.
+    ///
+    /// On stereo cameras, the left imager has index 1 and the right imager has index 2.
+    pub fn infrared_frame(&self, index: usize) -> Result<Option<VideoFrame>> {
+        self.first_matching::<frame_kind::Video, _>(|data| {
+            data.stream == StreamKind::Infrared && data.index == index
+        })
+    }
+
+    /// Gets the fisheye frame by stream index.
+    ///
+    /// On T265, the left camera has index 1 and the right camera has index 2.
+    pub fn fisheye_frame(&self, index: usize) -> Result<Option<VideoFrame>> {
+        self.first_matching::<frame_kind::Video, _>(|data| {
+            data.stream == StreamKind::Fisheye && data.index == index
+        })
+    }
+
+    /// Gets the first gyroscope frame.
+    pub fn gyro_frame(&self) -> Result<Option<MotionFrame>> {
+        self.first_of::<frame_kind::Motion>(StreamKind::Gyro)
+    }
+
+    /// Gets the first accelerometer frame.
+    pub fn accel_frame(&self) -> Result<Option<MotionFrame>> {
+        self.first_of::<frame_kind::Motion>(StreamKind::Accel)
+    }
+
+    /// Gets the first depth confidence frame, which is produced by L500 devices.
+    pub fn confidence_frame(&self) -> Result<Option<VideoFrame>> {
+        self.first_of::<frame_kind::Video>(StreamKind::Confidence)
+    }
 }
 
 impl PoseFrame {
@@ -718,6 +1103,44 @@ impl PointsFrame {
         }
     }
 
+    /// Gets the vertices as a `height × width` array view without copying.
+    ///
+    /// The shape follows [points_resolution](PointsFrame::points_resolution).
+    #[cfg(feature = "with-ndarray")]
+    pub fn vertices_array(&self) -> Result<ArrayView2<'_, [f32; 3]>> {
+        let Resolution { width, height } = self.points_resolution()?;
+        let vertices = self.vertices()?;
+
+        // SAFETY: rs2_vertex is a C struct containing a single float[3] array.
+        let xyz =
+            unsafe { slice::from_raw_parts(vertices.as_ptr() as *const [f32; 3], vertices.len()) };
+        ArrayView2::from_shape((height, width), xyz)
+            .map_err(|err| Error::InvalidData(format!("invalid points layout: {}", err)))
+    }
+
+    /// Gets the layout of points.
+    ///
+    /// Points computed from a depth frame are organized like the depth image, in which case
+    /// the resolution of the depth stream is returned. Otherwise, the points are regarded as
+    /// a single row.
+    pub fn points_resolution(&self) -> Result<Resolution> {
+        let n_points = self.points_count()?;
+        let profile = self
+            .stream_profile()?
+            .try_extend_to::<stream_profile_kind::Video>()?;
+        if let Ok(profile) = profile {
+            let resolution = profile.resolution()?;
+            if resolution.width * resolution.height == n_points {
+                return Ok(resolution);
+            }
+        }
+
+        Ok(Resolution {
+            width: n_points,
+            height: 1,
+        })
+    }
+
     /// Gets number of points in frame.
     pub fn points_count(&self) -> Result<usize> {
         unsafe {
@@ -767,6 +1190,7 @@ where
     Kind: frame_kind::FrameKind,
 {
     fn drop(&mut self) {
+        self.release_handle();
         unsafe {
             sys::rs2_release_frame(self.ptr.as_ptr());
         }
@@ -776,6 +1200,171 @@ where
 unsafe impl<Kind> Send for Frame<Kind> where Kind: frame_kind::FrameKind {}
 unsafe impl<Kind> Sync for Frame<Kind> where Kind: frame_kind::FrameKind {}
 
+/// The counts of [Frame] handles held by the process.
+///
+/// They count Rust handles rather than librealsense frames. Each clone counts as a handle,
+/// and a composite frame and the sub-frames extracted from it are counted separately,
+/// although the composite frame holds its sub-frames. The counts are thus an upper bound
+/// of the frames held from the frame pool, useful to spot handles that are never dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FrameHandleStats {
+    /// The number of frame handles alive.
+    pub handles: usize,
+    /// The number of alive handles marked by [Frame::keep].
+    pub kept_handles: usize,
+}
+
+impl FrameHandleStats {
+    /// Takes a snapshot of the current counts.
+    pub fn current() -> Self {
+        Self {
+            handles: FRAME_HANDLES.load(Ordering::SeqCst),
+            kept_handles: KEPT_FRAME_HANDLES.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Gets the number of alive handles not marked by [Frame::keep].
+    pub fn unkept_handles(&self) -> usize {
+        self.handles.saturating_sub(self.kept_handles)
+    }
+}
+
+/// A video frame that can be buffered without starving the frame pool.
+///
+/// It is created by [keep](OwnedVideoFrame::keep), which detaches the frame from the pool
+/// without copying, or by [copy](OwnedVideoFrame::copy), which copies the data and leaves
+/// the frame untouched. The data of a kept frame is copied on the first call to
+/// [data_mut](OwnedVideoFrame::data_mut).
+#[derive(Debug, Clone)]
+pub struct OwnedVideoFrame {
+    data: OwnedFrameData,
+    resolution: Resolution,
+    stride_in_bytes: usize,
+    bits_per_pixel: usize,
+    profile: StreamProfileData,
+    number: u64,
+    timestamp: f64,
+    timestamp_domain: TimestampDomain,
+}
+
+#[derive(Debug, Clone)]
+enum OwnedFrameData {
+    Kept(AnyFrame),
+    Copied(Vec<u8>),
+}
+
+impl OwnedVideoFrame {
+    /// Detaches the frame from the frame pool and takes it without copying.
+    pub fn keep<Kind>(frame: Frame<Kind>) -> Result<Self>
+    where
+        Kind: frame_kind::FrameKind,
+        Frame<Kind>: VideoFrameEx,
+    {
+        let mut owned = Self::with_header(&frame)?;
+        frame.keep();
+        owned.data = OwnedFrameData::Kept(frame.into_any());
+        Ok(owned)
+    }
+
+    /// Copies the data and properties of the frame.
+    pub fn copy<Kind>(frame: &Frame<Kind>) -> Result<Self>
+    where
+        Kind: frame_kind::FrameKind,
+        Frame<Kind>: VideoFrameEx,
+    {
+        let mut owned = Self::with_header(frame)?;
+        owned.data = OwnedFrameData::Copied(frame.data()?.to_vec());
+        Ok(owned)
+    }
+
+    fn with_header<Kind>(frame: &Frame<Kind>) -> Result<Self>
+    where
+        Kind: frame_kind::FrameKind,
+        Frame<Kind>: VideoFrameEx,
+    {
+        let owned = Self {
+            data: OwnedFrameData::Copied(vec![]),
+            resolution: frame.resolution()?,
+            stride_in_bytes: frame.stride_in_bytes()?,
+            bits_per_pixel: frame.bits_per_pixel()?,
+            profile: frame.stream_profile()?.get_data()?,
+            number: frame.number()?,
+            timestamp: frame.timestamp()?,
+            timestamp_domain: frame.timestamp_domain()?,
+        };
+        Ok(owned)
+    }
+
+    pub fn resolution(&self) -> &Resolution {
+        &self.resolution
+    }
+
+    pub fn width(&self) -> usize {
+        self.resolution.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.resolution.height
+    }
+
+    pub fn stride_in_bytes(&self) -> usize {
+        self.stride_in_bytes
+    }
+
+    pub fn bits_per_pixel(&self) -> usize {
+        self.bits_per_pixel
+    }
+
+    /// Gets the stream specification of the original frame.
+    pub fn profile(&self) -> &StreamProfileData {
+        &self.profile
+    }
+
+    pub fn number(&self) -> u64 {
+        self.number
+    }
+
+    pub fn timestamp(&self) -> f64 {
+        self.timestamp
+    }
+
+    pub fn timestamp_domain(&self) -> TimestampDomain {
+        self.timestamp_domain
+    }
+
+    /// Checks if the data is copied out of the original frame.
+    pub fn is_copied(&self) -> bool {
+        matches!(self.data, OwnedFrameData::Copied(_))
+    }
+
+    /// Gets raw data bytes.
+    pub fn data(&self) -> Result<&[u8]> {
+        match &self.data {
+            OwnedFrameData::Kept(frame) => frame.data(),
+            OwnedFrameData::Copied(data) => Ok(data),
+        }
+    }
+
+    /// Gets mutable raw data bytes, copying the data out of the original frame if needed.
+    pub fn data_mut(&mut self) -> Result<&mut [u8]> {
+        if let OwnedFrameData::Kept(frame) = &self.data {
+            self.data = OwnedFrameData::Copied(frame.data()?.to_vec());
+        }
+        match &mut self.data {
+            OwnedFrameData::Copied(data) => Ok(data),
+            OwnedFrameData::Kept(_) => unreachable!(),
+        }
+    }
+
+    /// Takes the raw data bytes, copying the data out of the original frame if needed.
+    pub fn into_vec(self) -> Result<Vec<u8>> {
+        match self.data {
+            OwnedFrameData::Kept(frame) => Ok(frame.data()?.to_vec()),
+            OwnedFrameData::Copied(data) => Ok(data),
+        }
+    }
+}
+
 /// The iterator type returned by [Frame::try_into_iter](Frame::try_into_iter).
 #[derive(Debug)]
 pub struct CompositeFrameIntoIter {
@@ -820,6 +1409,9 @@ impl Iterator for CompositeFrameIntoIter {
 
 impl FusedIterator for CompositeFrameIntoIter {}
 
+// the iterator owns a reference to the composite frame
+unsafe impl Send for CompositeFrameIntoIter {}
+
 impl Drop for CompositeFrameIntoIter {
     fn drop(&mut self) {
         unsafe {
@@ -6,12 +6,20 @@ use crate::{
     error::{Error as RsError, ErrorChecker, Result},
     frame::{AnyFrame, Frame, GenericFrameEx},
     frame_kind::FrameKind,
+    wait_future::WaitFuture,
 };
+use futures::stream::Stream;
 
 /// The queue of frames.
 #[derive(Debug)]
 pub struct FrameQueue {
-    pub(crate) ptr: NonNull<sys::rs2_frame_queue>,
+    handle: Arc<FrameQueueHandle>,
+}
+
+/// The owned queue pointer, shared with processing blocks and background waits.
+#[derive(Debug)]
+struct FrameQueueHandle {
+    ptr: NonNull<sys::rs2_frame_queue>,
 }
 
 impl FrameQueue {
@@ -32,7 +40,7 @@ impl FrameQueue {
         Kind: FrameKind,
     {
         unsafe {
-            sys::rs2_enqueue_frame(frame.ptr.as_ptr(), self.ptr.cast::<c_void>().as_ptr());
+            sys::rs2_enqueue_frame(frame.ptr.as_ptr(), self.ptr().cast::<c_void>().as_ptr());
         }
     }
 
@@ -45,7 +53,7 @@ impl FrameQueue {
         let frame = loop {
             let mut checker = ErrorChecker::new();
             let ptr = unsafe {
-                sys::rs2_wait_for_frame(self.ptr.as_ptr(), timeout_ms, checker.inner_mut_ptr())
+                sys::rs2_wait_for_frame(self.ptr().as_ptr(), timeout_ms, checker.inner_mut_ptr())
             };
 
             match (timeout, checker.check()) {
@@ -63,34 +71,30 @@ impl FrameQueue {
     }
 
     /// Wait for frame asynchronously. It is analogous to [FrameQueue::wait]
-    pub async fn wait_async(&mut self, timeout: Option<Duration>) -> Result<AnyFrame> {
-        let timeout_ms = timeout
-            .map(|duration| duration.as_millis() as c_uint)
-            .unwrap_or(sys::RS2_DEFAULT_TIMEOUT as c_uint);
-        let (tx, rx) = futures::channel::oneshot::channel();
-        let queue_ptr = AtomicPtr::new(self.ptr.as_ptr());
-
-        thread::spawn(move || {
-            let result = unsafe {
-                loop {
-                    let mut checker = ErrorChecker::new();
-                    let ptr = sys::rs2_wait_for_frame(
-                        queue_ptr.load(Ordering::Relaxed),
-                        timeout_ms,
-                        checker.inner_mut_ptr(),
-                    );
-                    let result = match (timeout, checker.check()) {
-                        (None, Err(RsError::Timeout(..))) => continue,
-                        (_, result) => result.map(|_| Frame::from_raw(ptr)),
-                    };
-                    break result;
-                }
+    ///
+    /// Dropping the returned future stops the background wait.
+    pub fn wait_async(&mut self, timeout: Option<Duration>) -> WaitFuture<Result<AnyFrame>> {
+        let handle = self.handle.clone();
+
+        WaitFuture::new(timeout, move |interval, is_last| {
+            let mut checker = ErrorChecker::new();
+            let ptr = unsafe {
+                sys::rs2_wait_for_frame(
+                    handle.ptr.as_ptr(),
+                    interval.as_millis() as c_uint,
+                    checker.inner_mut_ptr(),
+                )
             };
-            let _ = tx.send(result);
-        });
+            match checker.check() {
+                Err(RsError::Timeout(..)) if !is_last => None,
+                result => Some(result.map(|_| unsafe { Frame::from_raw(ptr) })),
+            }
+        })
+    }
 
-        let frame = rx.await.unwrap()?;
-        Ok(frame)
+    /// Iterates over frames available in the queue without blocking.
+    pub fn try_iter(&mut self) -> FrameQueueTryIter<'_> {
+        FrameQueueTryIter { queue: self }
     }
 
     /// Try to pop a frame and returns immediately.
@@ -99,7 +103,7 @@ impl FrameQueue {
             let mut checker = ErrorChecker::new();
             let mut ptr: *mut sys::rs2_frame = ptr::null_mut();
             let ret = sys::rs2_poll_for_frame(
-                self.ptr.as_ptr(),
+                self.ptr().as_ptr(),
                 &mut ptr as *mut _,
                 checker.inner_mut_ptr(),
             );
@@ -114,24 +118,37 @@ impl FrameQueue {
         }
     }
 
+    /// Destructs and returns the raw pointer.
+    ///
+    /// The pointer is not deleted by pending [wait_async](FrameQueue::wait_async) futures
+    /// or processing blocks sharing the queue, but it must outlive them.
     pub fn into_raw(self) -> *mut sys::rs2_frame_queue {
-        let ptr = self.ptr;
+        let ptr = self.ptr();
         mem::forget(self);
         ptr.as_ptr()
     }
 
     pub unsafe fn from_raw(ptr: *mut sys::rs2_frame_queue) -> Self {
         Self {
-            ptr: NonNull::new(ptr).unwrap(),
+            handle: Arc::new(FrameQueueHandle {
+                ptr: NonNull::new(ptr).unwrap(),
+            }),
         }
     }
 
-    pub(crate) unsafe fn unsafe_clone(&self) -> Self {
-        Self { ptr: self.ptr }
+    pub(crate) fn ptr(&self) -> NonNull<sys::rs2_frame_queue> {
+        self.handle.ptr
+    }
+
+    /// Creates another handle to the same queue.
+    pub(crate) fn share(&self) -> Self {
+        Self {
+            handle: self.handle.clone(),
+        }
     }
 }
 
-impl Drop for FrameQueue {
+impl Drop for FrameQueueHandle {
     fn drop(&mut self) {
         unsafe {
             sys::rs2_delete_frame_queue(self.ptr.as_ptr());
@@ -139,4 +156,44 @@ impl Drop for FrameQueue {
     }
 }
 
-unsafe impl Send for FrameQueue {}
+unsafe impl Send for FrameQueueHandle {}
+
+// SAFETY: A librealsense frame queue is a single-consumer queue guarded by a mutex and
+// condition variable, so enqueueing, waiting and polling may be called from any thread.
+// The handle is shared by [FrameQueue]s, processing blocks and background waits, which only
+// call these functions.
+unsafe impl Sync for FrameQueueHandle {}
+
+/// The iterator type returned by [FrameQueue::try_iter](FrameQueue::try_iter).
+#[derive(Debug)]
+pub struct FrameQueueTryIter<'a> {
+    queue: &'a mut FrameQueue,
+}
+
+impl<'a> Iterator for FrameQueueTryIter<'a> {
+    type Item = Result<AnyFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.queue.try_wait().transpose()
+    }
+}
+
+/// The stream of frames delivered by a librealsense callback.
+#[derive(Debug)]
+pub struct FrameStream {
+    rx: futures::channel::mpsc::Receiver<AnyFrame>,
+}
+
+impl FrameStream {
+    pub(crate) fn new(rx: futures::channel::mpsc::Receiver<AnyFrame>) -> Self {
+        Self { rx }
+    }
+}
+
+impl Stream for FrameStream {
+    type Item = AnyFrame;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.rx).poll_next(cx)
+    }
+}
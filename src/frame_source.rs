@@ -0,0 +1,230 @@
+//! Defines the types to implement custom processing blocks.
+
+use crate::{
+    callback::CallbackContext,
+    common::*,
+    error::{Error, ErrorChecker, Result},
+    frame::{AnyFrame, Frame, GenericFrameEx},
+    frame_kind::FrameKind,
+    kind::{Extension, Rs2Option},
+    stream_profile::StreamProfile,
+    stream_profile_kind::StreamProfileKind,
+};
+
+/// The trait of user-defined frame processing.
+///
+/// It is used to create a [CustomProcessingBlock](crate::processing_block::CustomProcessingBlock).
+/// The processing runs on the processing thread of librealsense. Output frames are
+/// sent to the block output by [FrameSource::frame_ready].
+pub trait FrameProcessor
+where
+    Self: Send + 'static,
+{
+    /// Processes an input frame.
+    ///
+    /// The last returned error is kept by the block, and can be taken by
+    /// [CustomProcessingBlock::take_processor_error](crate::processing_block::CustomProcessingBlock::take_processor_error).
+    fn process(&mut self, input: AnyFrame, source: &FrameSource) -> Result<()>;
+}
+
+impl<F> FrameProcessor for F
+where
+    F: FnMut(AnyFrame, &FrameSource) -> Result<()> + Send + 'static,
+{
+    fn process(&mut self, input: AnyFrame, source: &FrameSource) -> Result<()> {
+        (self)(input, source)
+    }
+}
+
+/// The frame allocator and output of a custom processing block.
+///
+/// It is only available within [FrameProcessor::process].
+#[derive(Debug)]
+pub struct FrameSource {
+    ptr: NonNull<sys::rs2_source>,
+    block_ptr: NonNull<sys::rs2_processing_block>,
+}
+
+impl FrameSource {
+    /// Allocates a new video frame derived from the original frame.
+    ///
+    /// The new frame inherits the stream profile of `original` if `profile` is `None`.
+    /// The `frame_type` is usually [VideoFrame](Extension::VideoFrame) or
+    /// [DepthFrame](Extension::DepthFrame).
+    #[allow(clippy::too_many_arguments)]
+    pub fn allocate_video_frame<K, P>(
+        &self,
+        original: &Frame<K>,
+        profile: Option<&StreamProfile<P>>,
+        bytes_per_pixel: usize,
+        width: usize,
+        height: usize,
+        stride_in_bytes: usize,
+        frame_type: Extension,
+    ) -> Result<SyntheticFrame>
+    where
+        K: FrameKind,
+        P: StreamProfileKind,
+    {
+        unsafe {
+            let profile_ptr = match profile {
+                Some(profile) => profile.ptr().as_ptr() as *const _,
+                None => {
+                    let mut checker = ErrorChecker::new();
+                    let ptr = sys::rs2_get_frame_stream_profile(
+                        original.ptr().as_ptr(),
+                        checker.inner_mut_ptr(),
+                    );
+                    checker.check()?;
+                    ptr
+                }
+            };
+
+            let mut checker = ErrorChecker::new();
+            let ptr = sys::rs2_allocate_synthetic_video_frame(
+                self.ptr.as_ptr(),
+                profile_ptr,
+                original.ptr().as_ptr(),
+                // librealsense converts bytes per pixel to bits by itself
+                bytes_per_pixel as c_int,
+                width as c_int,
+                height as c_int,
+                stride_in_bytes as c_int,
+                frame_type as sys::rs2_extension,
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+
+            let frame = SyntheticFrame {
+                frame: AnyFrame::from_raw(ptr),
+                width,
+                height,
+                stride_in_bytes,
+            };
+            Ok(frame)
+        }
+    }
+
+    /// Sends an allocated frame to the output of processing block.
+    pub fn frame_ready(&self, frame: SyntheticFrame) -> Result<()> {
+        self.send(frame.frame)
+    }
+
+    /// Sends an existing frame to the output of processing block without modification.
+    pub fn pass_through<K>(&self, frame: Frame<K>) -> Result<()>
+    where
+        K: FrameKind,
+    {
+        self.send(frame)
+    }
+
+    fn send<K>(&self, frame: Frame<K>) -> Result<()>
+    where
+        K: FrameKind,
+    {
+        unsafe {
+            let frame_ptr = frame.into_raw();
+            let mut checker = ErrorChecker::new();
+            sys::rs2_synthetic_frame_ready(self.ptr.as_ptr(), frame_ptr, checker.inner_mut_ptr());
+            checker.check()?;
+        }
+        Ok(())
+    }
+
+    /// Gets the current value of an option registered on the processing block.
+    pub fn option_value(&self, option: Rs2Option) -> Result<f32> {
+        unsafe {
+            let mut checker = ErrorChecker::new();
+            let val = sys::rs2_get_option(
+                self.block_ptr.cast::<sys::rs2_options>().as_ptr(),
+                option as sys::rs2_option,
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+            Ok(val)
+        }
+    }
+}
+
+/// A newly allocated video frame with writable data.
+///
+/// It is returned by [FrameSource::allocate_video_frame] and is sent to
+/// the block output by [FrameSource::frame_ready].
+#[derive(Debug)]
+pub struct SyntheticFrame {
+    frame: AnyFrame,
+    width: usize,
+    height: usize,
+    stride_in_bytes: usize,
+}
+
+impl SyntheticFrame {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn stride_in_bytes(&self) -> usize {
+        self.stride_in_bytes
+    }
+
+    /// Gets the mutable raw data bytes in frame.
+    pub fn data_mut(&mut self) -> Result<&mut [u8]> {
+        let size = self.frame.data_size()?;
+        let slice = unsafe {
+            let mut checker = ErrorChecker::new();
+            let ptr = sys::rs2_get_frame_data(self.frame.ptr().as_ptr(), checker.inner_mut_ptr());
+            checker.check()?;
+            slice::from_raw_parts_mut::<u8>(ptr as *mut u8, size)
+        };
+        Ok(slice)
+    }
+}
+
+/// The context passed to librealsense along with [processor_callback].
+pub(crate) struct ProcessorContext<P>
+where
+    P: FrameProcessor,
+{
+    pub(crate) processor: P,
+    pub(crate) block_ptr: Option<NonNull<sys::rs2_processing_block>>,
+    pub(crate) error: Arc<Mutex<Option<Error>>>,
+}
+
+/// Owns the processor of a custom processing block along with its last error.
+#[derive(Debug)]
+pub(crate) struct ProcessorHandle {
+    // kept alive for the callback registered on the block
+    pub(crate) _context: CallbackContext,
+    pub(crate) error: Arc<Mutex<Option<Error>>>,
+}
+
+// the processing block pointer is only used for thread-safe option access
+unsafe impl<P> Send for ProcessorContext<P> where P: FrameProcessor {}
+
+pub(crate) unsafe extern "C" fn processor_callback<P>(
+    frame: *mut sys::rs2_frame,
+    source: *mut sys::rs2_source,
+    context: *mut c_void,
+) where
+    P: FrameProcessor,
+{
+    // unwinding across FFI boundary is undefined behavior
+    let _ = panic::catch_unwind(|| {
+        // the frame is owned by the callback
+        let input = AnyFrame::from_raw(frame);
+        let context = &mut *(context as *mut ProcessorContext<P>);
+        let source = FrameSource {
+            ptr: NonNull::new(source).unwrap(),
+            block_ptr: context.block_ptr.unwrap(),
+        };
+        if let Err(err) = context.processor.process(input, &source) {
+            if let Ok(mut error) = context.error.lock() {
+                *error = Some(err);
+            }
+        }
+    });
+}
@@ -0,0 +1,113 @@
+//! Defines the frame synchronizer for sensors streaming without pipeline.
+
+use crate::{
+    common::*,
+    error::{Error, ErrorChecker, Result},
+    frame::{AnyFrame, CompositeFrame, Frame, GenericFrameEx},
+    frame_kind::FrameKind,
+    frame_queue::FrameQueue,
+    processing_block::Syncer,
+};
+
+/// Matches frames from multiple sensors by timestamp and emits [CompositeFrame]s.
+///
+/// Frames are fed by [FrameSyncerSender]s, which are usually moved into the callbacks of
+/// [Sensor::start](crate::sensor::Sensor::start).
+#[derive(Debug)]
+pub struct FrameSyncer {
+    // the queue shared with the syncer block in inner
+    queue: FrameQueue,
+    inner: Arc<SyncerInner>,
+}
+
+/// The handle feeding frames to a [FrameSyncer].
+#[derive(Debug, Clone)]
+pub struct FrameSyncerSender {
+    inner: Arc<SyncerInner>,
+}
+
+#[derive(Debug)]
+struct SyncerInner {
+    block: Syncer,
+}
+
+// SAFETY: The block is private to this module, and [FrameSyncerSender::send] is the only code
+// using it through a shared reference. It only calls `rs2_process_frame`, which is safe to call
+// concurrently on a syncer, since the librealsense syncer serializes the matching of incoming
+// frames by its internal mutex. The output queue is read by [FrameSyncer] alone through its own
+// handle, and the block fields are never mutated after construction.
+unsafe impl Sync for SyncerInner {}
+
+impl FrameSyncer {
+    /// Creates a syncer buffering at most `capacity` output frames.
+    pub fn new(capacity: usize) -> Result<Self> {
+        let mut block = Syncer::create()?;
+        block.set_output_queue_capacity(capacity)?;
+        let queue = block.output_queue().share();
+        let syncer = Self {
+            queue,
+            inner: Arc::new(SyncerInner { block }),
+        };
+        Ok(syncer)
+    }
+
+    /// Creates a handle to feed frames.
+    pub fn sender(&self) -> FrameSyncerSender {
+        FrameSyncerSender {
+            inner: self.inner.clone(),
+        }
+    }
+
+    /// Creates a callback for [Sensor::start](crate::sensor::Sensor::start) feeding frames to the syncer.
+    pub fn sensor_callback(&self) -> impl FnMut(AnyFrame) + Send + 'static {
+        let sender = self.sender();
+        move |frame| {
+            let _ = sender.send(frame);
+        }
+    }
+
+    /// Waits for a synchronized frame set.
+    ///
+    /// The method blocks forever if `timeout` is `None`.
+    pub fn wait(&mut self, timeout: Option<Duration>) -> Result<CompositeFrame> {
+        let frame = self.queue.wait(timeout)?;
+        into_composite(frame)
+    }
+
+    /// Waits for a synchronized frame set asynchronously. It is analogous to [FrameSyncer::wait].
+    pub async fn wait_async(&mut self, timeout: Option<Duration>) -> Result<CompositeFrame> {
+        let frame = self.queue.wait_async(timeout).await?;
+        into_composite(frame)
+    }
+
+    /// Try to get a synchronized frame set and returns immediately.
+    pub fn try_wait(&mut self) -> Result<Option<CompositeFrame>> {
+        self.queue.try_wait()?.map(into_composite).transpose()
+    }
+}
+
+impl FrameSyncerSender {
+    /// Feeds a frame to the syncer.
+    pub fn send<K>(&self, frame: Frame<K>) -> Result<()>
+    where
+        K: FrameKind,
+    {
+        unsafe {
+            let frame_ptr = frame.into_raw();
+            let mut checker = ErrorChecker::new();
+            sys::rs2_process_frame(
+                self.inner.block.ptr.as_ptr(),
+                frame_ptr,
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+        }
+        Ok(())
+    }
+}
+
+fn into_composite(frame: AnyFrame) -> Result<CompositeFrame> {
+    frame
+        .try_extend_to()?
+        .map_err(|_| Error::InvalidData("syncer output is not a composite frame".into()))
+}
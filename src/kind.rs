@@ -1,6 +1,9 @@
 //! Defines the common used enums.
 
-use crate::common::*;
+use crate::{
+    common::*,
+    error::{Error, Result},
+};
 
 /// The enumeration of options.
 #[repr(u32)]
@@ -69,6 +72,10 @@ pub enum Rs2Option {
     LedPower = sys::rs2_option_RS2_OPTION_LED_POWER,
     ZeroOrderEnabled = sys::rs2_option_RS2_OPTION_ZERO_ORDER_ENABLED,
     EnableMapPreservation = sys::rs2_option_RS2_OPTION_ENABLE_MAP_PRESERVATION,
+    HdrEnabled = sys::rs2_option_RS2_OPTION_HDR_ENABLED,
+    SequenceName = sys::rs2_option_RS2_OPTION_SEQUENCE_NAME,
+    SequenceSize = sys::rs2_option_RS2_OPTION_SEQUENCE_SIZE,
+    SequenceId = sys::rs2_option_RS2_OPTION_SEQUENCE_ID,
     Count = sys::rs2_option_RS2_OPTION_COUNT,
 }
 
@@ -159,6 +166,45 @@ pub enum FrameMetaDataValue {
     Count = sys::rs2_frame_metadata_value_RS2_FRAME_METADATA_COUNT,
 }
 
+impl FrameMetaDataValue {
+    pub fn to_cstr(&self) -> &'static CStr {
+        unsafe {
+            let ptr = sys::rs2_frame_metadata_to_string(*self as sys::rs2_frame_metadata_value);
+            CStr::from_ptr(ptr)
+        }
+    }
+
+    pub fn to_str(&self) -> Result<&'static str> {
+        self.to_cstr()
+            .to_str()
+            .map_err(|err| Error::InvalidData(format!("invalid metadata name: {}", err)))
+    }
+}
+
+impl Display for FrameMetaDataValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        f.write_str(&self.to_cstr().to_string_lossy())
+    }
+}
+
+/// The laser power mode in frame metadata.
+#[repr(u64)]
+#[derive(FromPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LaserPowerMode {
+    Off = 0,
+    On = 1,
+}
+
+/// The emitter mode in frame metadata.
+#[repr(u64)]
+#[derive(FromPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmitterMode {
+    Off = 0,
+    Laser = 1,
+    AutoLaser = 2,
+    Led = 3,
+}
+
 /// The enumeration of extensions.
 #[repr(u32)]
 #[derive(FromPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -190,6 +236,8 @@ pub enum Extension {
     HoleFillingFilter = sys::rs2_extension_RS2_EXTENSION_HOLE_FILLING_FILTER,
     ZeroOrderFilter = sys::rs2_extension_RS2_EXTENSION_ZERO_ORDER_FILTER,
     RecommendedFilters = sys::rs2_extension_RS2_EXTENSION_RECOMMENDED_FILTERS,
+    HdrMerge = sys::rs2_extension_RS2_EXTENSION_HDR_MERGE,
+    SequenceIdFilter = sys::rs2_extension_RS2_EXTENSION_SEQUENCE_ID_FILTER,
     // profile
     VideoProfile = sys::rs2_extension_RS2_EXTENSION_VIDEO_PROFILE,
     MotionProfile = sys::rs2_extension_RS2_EXTENSION_MOTION_PROFILE,
@@ -305,6 +353,16 @@ pub enum ColorScheme {
     Hue = 9,
 }
 
+/// The enumeration of colorizer visual presets.
+#[repr(usize)]
+#[derive(FromPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorizerPreset {
+    Dynamic = 0,
+    Fixed = 1,
+    Near = 2,
+    Far = 3,
+}
+
 /// The enumeration of persistence controls.
 #[repr(usize)]
 #[derive(FromPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -8,8 +8,21 @@
 //!
 //! - **with-nalgebra** (default): Enable [nalgebra](https://github.com/rustsim/nalgebra) support.
 //! - **with-image** (default): Enable [image](https://github.com/image-rs/image) support.
+//! - **with-mjpeg**: Enable decoding of MJPEG frames. It implies **with-image**.
+//! - **with-ndarray**: Enable [ndarray](https://github.com/rust-ndarray/ndarray) support.
 //! - **buildtime-bindgen**: Generate Rust bindings during build time.
 //! - **device-test**: Enable tests that requires connections to RealSense devices.
+//! - **tokio**: Run the blocking waits of async methods by `spawn_blocking` of tokio runtime.
+//! - **async-std**: Run the blocking waits of async methods by `spawn_blocking` of async-std.
+//!
+//! ## Thread Safety
+//!
+//! Frames are reference counted and immutable once delivered, and thus are [Send] and [Sync].
+//! [Context], [Device], [Pipeline], [PipelineProfile], [StreamProfile](stream_profile::StreamProfile),
+//! and [FrameQueue] are [Send] and [Sync].
+//!
+//! [Sensor](sensor::Sensor), [ProcessingBlock](processing_block::ProcessingBlock) and [Config] are
+//! only [Send], since their shared methods change options or settings without synchronization.
 //!
 //! ## Get Started
 //!
@@ -35,18 +48,28 @@
 //! ```
 
 pub mod base;
+pub mod blocking;
+mod callback;
+#[cfg(feature = "with-image")]
+pub mod colormap;
 mod common;
 pub mod config;
 pub mod context;
+#[cfg(feature = "with-image")]
+pub mod decode;
 pub mod device;
 pub mod device_hub;
 pub mod device_list;
 pub mod error;
+pub mod filter_chain;
 pub mod frame;
 pub mod frame_kind;
 pub mod frame_queue;
+pub mod frame_source;
+pub mod frame_syncer;
 pub mod kind;
 pub mod options;
+pub mod pcd;
 pub mod pipeline;
 pub mod pipeline_kind;
 pub mod pipeline_profile;
@@ -59,6 +82,9 @@ pub mod sensor_list;
 pub mod stream_profile;
 pub mod stream_profile_kind;
 pub mod stream_profile_list;
+pub mod stream_stats;
+pub mod time_sync;
+pub mod wait_future;
 
 /// The mod collects common used traits from this crate.
 pub mod prelude {
@@ -67,29 +93,37 @@ pub mod prelude {
 
 #[cfg(feature = "with-image")]
 pub use base::Rs2Image;
-pub use base::{Extrinsics, Intrinsics, MotionIntrinsics, PoseData, Resolution, StreamProfileData};
+pub use base::{
+    Extrinsics, FrameMetadata, Intrinsics, MotionIntrinsics, PoseData, Rect, RegionStats,
+    Resolution, StreamProfileData,
+};
 pub use config::Config;
 pub use context::Context;
 pub use device::Device;
 pub use device_hub::DeviceHub;
 pub use device_list::{DeviceList, DeviceListIntoIter};
 pub use error::{Error, Result};
+pub use filter_chain::FilterChain;
 pub use frame::{
     CompositeFrameIntoIter, DepthFrame, DepthFrameEx, DisparityFrame, DisparityFrameEx,
-    ExtendedFrame, Frame, GenericFrameEx, VideoFrame, VideoFrameEx,
+    ExtendedFrame, Frame, FrameHandleStats, GenericFrameEx, OwnedVideoFrame, VideoFrame,
+    VideoFrameEx,
 };
-pub use frame_queue::FrameQueue;
+pub use frame_queue::{FrameQueue, FrameQueueTryIter, FrameStream};
+pub use frame_source::{FrameProcessor, FrameSource, SyntheticFrame};
+pub use frame_syncer::{FrameSyncer, FrameSyncerSender};
 pub use kind::{
-    CameraInfo, ColorScheme, Extension, Format, FrameMetaDataValue, HoleFillingMode,
-    PersistenceControl, Rs2Option, StreamKind, TimestampDomain,
+    CameraInfo, ColorScheme, ColorizerPreset, EmitterMode, Extension, Format, FrameMetaDataValue,
+    HoleFillingMode, LaserPowerMode, PersistenceControl, Rs2Option, StreamKind, TimestampDomain,
 };
-pub use options::{OptionHandle, ToOptions};
-pub use pipeline::{ActivePipeline, InactivePipeline, Pipeline};
+pub use options::{OptionHandle, OptionRange, ToOptions};
+pub use pipeline::{ActivePipeline, DropPolicy, InactivePipeline, Pipeline, PipelineFrameStream};
 pub use pipeline_profile::PipelineProfile;
 pub use processing_block::{
-    Align, AnyProcessingBlock, Colorizer, DecimationFilter, DisparityFilter, HoleFillingFilter,
-    HuffmanDepthDecompress, PointCloud, ProcessingBlock, RatesPrinter, SpatialFilter, Syncer,
-    TemporalFilter, ThresholdFilter, UnitsTransform, YuyDecoder, ZeroOrderFilter,
+    Align, AlignedFrames, AnyProcessingBlock, Colorizer, CustomProcessingBlock, DecimationFilter,
+    DisparityFilter, HdrMerge, HoleFillingFilter, HuffmanDepthDecompress, PointCloud,
+    ProcessingBlock, RatesPrinter, SequenceIdFilter, SpatialFilter, Syncer, TemporalFilter,
+    ThresholdFilter, UnitsTransform, YuyDecoder, ZeroOrderFilter,
 };
 pub use processing_block_list::{ProcessingBlockList, ProcessingBlockListIntoIter};
 pub use sensor::{
@@ -101,3 +135,6 @@ pub use stream_profile::{
     AnyStreamProfile, MotionStreamProfile, PoseStreamProfile, StreamProfile, VideoStreamProfile,
 };
 pub use stream_profile_list::{StreamProfileList, StreamProfileListIntoIter};
+pub use stream_stats::{JitterHistogram, LatencyStats, StreamReport, StreamStats};
+pub use time_sync::TimeSync;
+pub use wait_future::WaitFuture;
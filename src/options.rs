@@ -2,7 +2,7 @@
 
 use crate::{
     common::*,
-    error::{ErrorChecker, Result},
+    error::{Error, ErrorChecker, Result},
     kind::Rs2Option,
 };
 
@@ -10,65 +10,103 @@ use crate::{
 pub trait ToOptions {
     fn to_options(&self) -> Result<HashMap<Rs2Option, OptionHandle>> {
         let options_ptr = self.options_ptr();
-        unsafe {
-            let list_ptr = {
-                let mut checker = ErrorChecker::new();
-                let list_ptr =
-                    sys::rs2_get_options_list(options_ptr.as_ptr(), checker.inner_mut_ptr());
-                checker.check()?;
-                list_ptr
-            };
+        let handles = raw_option_ids(options_ptr)?
+            .into_iter()
+            .filter_map(|val| {
+                let option = Rs2Option::from_u32(val)?;
+                let handle = OptionHandle {
+                    ptr: options_ptr,
+                    option: val,
+                };
+                Some((option, handle))
+            })
+            .collect();
+        Ok(handles)
+    }
 
-            let len = {
-                let mut checker = ErrorChecker::new();
-                let len = sys::rs2_get_options_list_size(list_ptr, checker.inner_mut_ptr());
-                checker.check()?;
-                len
-            };
+    fn options_ptr(&self) -> NonNull<sys::rs2_options>;
+}
 
-            let handles = (0..len)
-                .filter_map(|index| {
-                    let mut checker = ErrorChecker::new();
-                    let val =
-                        sys::rs2_get_option_from_list(list_ptr, index, checker.inner_mut_ptr());
-                    if let Err(err) = checker.check() {
-                        return Some(Err(err));
-                    }
-                    let option = match Rs2Option::from_u32(val) {
-                        Some(option) => option,
-                        None => return None,
-                    };
-                    let handle = OptionHandle {
-                        ptr: options_ptr,
-                        option,
-                    };
-
-                    Some(Result::Ok((option, handle)))
-                })
-                .collect::<Result<HashMap<_, _>>>()?;
-            Ok(handles)
-        }
+/// Lists the raw IDs of supported options, including those without [Rs2Option] counterpart.
+pub(crate) fn raw_option_ids(
+    options_ptr: NonNull<sys::rs2_options>,
+) -> Result<Vec<sys::rs2_option>> {
+    unsafe {
+        let list_ptr = {
+            let mut checker = ErrorChecker::new();
+            let list_ptr = sys::rs2_get_options_list(options_ptr.as_ptr(), checker.inner_mut_ptr());
+            checker.check()?;
+            list_ptr
+        };
+        let ids = options_list_ids(list_ptr);
+        sys::rs2_delete_options_list(list_ptr);
+        ids
     }
+}
 
-    fn options_ptr(&self) -> NonNull<sys::rs2_options>;
+unsafe fn options_list_ids(list_ptr: *const sys::rs2_options_list) -> Result<Vec<sys::rs2_option>> {
+    let len = {
+        let mut checker = ErrorChecker::new();
+        let len = sys::rs2_get_options_list_size(list_ptr, checker.inner_mut_ptr());
+        checker.check()?;
+        len
+    };
+
+    (0..len)
+        .map(|index| {
+            let mut checker = ErrorChecker::new();
+            let val = sys::rs2_get_option_from_list(list_ptr, index, checker.inner_mut_ptr());
+            checker.check()?;
+            Ok(val)
+        })
+        .collect()
+}
+
+/// The tolerance of [OptionRange::contains] in fractions of a step.
+const STEP_TOLERANCE: f32 = 1e-3;
+
+/// The valid range of an option value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptionRange {
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    pub default: f32,
+}
+
+impl OptionRange {
+    /// Checks if the value is within the range and lies on a step from the minimum.
+    ///
+    /// Values off by a small fraction of the step are accepted to tolerate rounding errors.
+    pub fn contains(&self, value: f32) -> bool {
+        if !(value >= self.min && value <= self.max) {
+            return false;
+        }
+        if self.step <= 0.0 {
+            return true;
+        }
+        let steps = (value - self.min) / self.step;
+        (steps - steps.round()).abs() <= STEP_TOLERANCE
+    }
 }
 
 /// A handle pointing to the option value.
 #[derive(Debug, Clone)]
 pub struct OptionHandle {
     ptr: NonNull<sys::rs2_options>,
-    option: Rs2Option,
+    option: sys::rs2_option,
 }
 
 impl OptionHandle {
+    /// Creates a handle from a raw option ID, which may have no [Rs2Option] counterpart.
+    pub(crate) fn from_raw_parts(ptr: NonNull<sys::rs2_options>, option: sys::rs2_option) -> Self {
+        Self { ptr, option }
+    }
+
     pub fn value(&self) -> Result<f32> {
         unsafe {
             let mut checker = ErrorChecker::new();
-            let val = sys::rs2_get_option(
-                self.ptr.as_ptr(),
-                self.option as sys::rs2_option,
-                checker.inner_mut_ptr(),
-            );
+            let val = sys::rs2_get_option(self.ptr.as_ptr(), self.option, checker.inner_mut_ptr());
             checker.check()?;
             Ok(val)
         }
@@ -79,7 +117,7 @@ impl OptionHandle {
             let mut checker = ErrorChecker::new();
             sys::rs2_set_option(
                 self.ptr.as_ptr(),
-                self.option as sys::rs2_option,
+                self.option,
                 value,
                 checker.inner_mut_ptr(),
             );
@@ -88,12 +126,56 @@ impl OptionHandle {
         }
     }
 
+    /// Sets the value after checking it against the [range](OptionHandle::range).
+    ///
+    /// It returns [Error::OptionOutOfRange] if the value is out of range or off the step.
+    pub fn set_value_checked(&self, value: f32) -> Result<()> {
+        let range = self.range()?;
+        if !range.contains(value) {
+            return Err(Error::OptionOutOfRange {
+                option: self.name().unwrap_or("unknown").to_owned(),
+                value,
+                range,
+            });
+        }
+        self.set_value(value)
+    }
+
+    /// Gets the valid range of option value.
+    pub fn range(&self) -> Result<OptionRange> {
+        unsafe {
+            let mut min = MaybeUninit::uninit();
+            let mut max = MaybeUninit::uninit();
+            let mut step = MaybeUninit::uninit();
+            let mut default = MaybeUninit::uninit();
+            let mut checker = ErrorChecker::new();
+            sys::rs2_get_option_range(
+                self.ptr.as_ptr(),
+                self.option,
+                min.as_mut_ptr(),
+                max.as_mut_ptr(),
+                step.as_mut_ptr(),
+                default.as_mut_ptr(),
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+
+            let range = OptionRange {
+                min: min.assume_init(),
+                max: max.assume_init(),
+                step: step.assume_init(),
+                default: default.assume_init(),
+            };
+            Ok(range)
+        }
+    }
+
     pub fn is_read_only(&self) -> Result<bool> {
         unsafe {
             let mut checker = ErrorChecker::new();
             let val = sys::rs2_is_option_read_only(
                 self.ptr.as_ptr(),
-                self.option as sys::rs2_option,
+                self.option,
                 checker.inner_mut_ptr(),
             );
             checker.check()?;
@@ -104,11 +186,8 @@ impl OptionHandle {
     pub fn name<'a>(&'a self) -> Result<&'a str> {
         unsafe {
             let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_get_option_name(
-                self.ptr.as_ptr(),
-                self.option as sys::rs2_option,
-                checker.inner_mut_ptr(),
-            );
+            let ptr =
+                sys::rs2_get_option_name(self.ptr.as_ptr(), self.option, checker.inner_mut_ptr());
             checker.check()?;
             let desc = CStr::from_ptr(ptr).to_str().unwrap();
             Ok(desc)
@@ -120,7 +199,7 @@ impl OptionHandle {
             let mut checker = ErrorChecker::new();
             let ptr = sys::rs2_get_option_description(
                 self.ptr.as_ptr(),
-                self.option as sys::rs2_option,
+                self.option,
                 checker.inner_mut_ptr(),
             );
             checker.check()?;
@@ -134,7 +213,7 @@ impl OptionHandle {
             let mut checker = ErrorChecker::new();
             let ptr = sys::rs2_get_option_value_description(
                 self.ptr.as_ptr(),
-                self.option as sys::rs2_option,
+                self.option,
                 value,
                 checker.inner_mut_ptr(),
             );
@@ -144,3 +223,48 @@ impl OptionHandle {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn option_range_contains() {
+        let range = OptionRange {
+            min: 0.25,
+            max: 1.0,
+            step: 0.01,
+            default: 0.5,
+        };
+        assert!(range.contains(0.25));
+        assert!(range.contains(0.5));
+        assert!(range.contains(1.0));
+        assert!(!range.contains(0.2));
+        assert!(!range.contains(1.01));
+        assert!(!range.contains(0.505));
+        assert!(!range.contains(f32::NAN));
+    }
+
+    #[test]
+    fn option_range_contains_integer_steps() {
+        let range = OptionRange {
+            min: 1.0,
+            max: 5.0,
+            step: 1.0,
+            default: 2.0,
+        };
+        assert!(range.contains(3.0));
+        assert!(!range.contains(2.5));
+    }
+
+    #[test]
+    fn option_range_contains_without_step() {
+        let range = OptionRange {
+            min: 0.0,
+            max: 1.0,
+            step: 0.0,
+            default: 0.0,
+        };
+        assert!(range.contains(0.123));
+    }
+}
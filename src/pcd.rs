@@ -0,0 +1,1012 @@
+//! Reading and writing point clouds in PCD (Point Cloud Library) format.
+//!
+//! The module converts [PointsFrame](crate::frame::PointsFrame)s into a [PointBuffer],
+//! which can be saved in `ascii`, `binary` or `binary_compressed` PCD encoding
+//! and read back from files produced by this crate or by PCL tools.
+
+use crate::{
+    base::{Resolution, StreamProfileData},
+    common::*,
+    error::{Error, Result},
+    frame::{GenericFrameEx, PointsFrame, VideoFrame, VideoFrameEx},
+};
+
+/// The encoding of the data section in a PCD file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataFormat {
+    Ascii,
+    Binary,
+    BinaryCompressed,
+}
+
+impl DataFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ascii => "ascii",
+            Self::Binary => "binary",
+            Self::BinaryCompressed => "binary_compressed",
+        }
+    }
+}
+
+/// An owned point cloud with optional color and intensity channels.
+///
+/// The cloud is organized if `height > 1`, in which case points are stored in row-major order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointBuffer {
+    pub width: usize,
+    pub height: usize,
+    pub points: Vec<[f32; 3]>,
+    pub colors: Option<Vec<[u8; 3]>>,
+    pub intensities: Option<Vec<f32>>,
+}
+
+impl PointBuffer {
+    /// Copies the vertices of a points frame.
+    ///
+    /// The buffer is organized as given by [PointsFrame::points_resolution].
+    pub fn from_points_frame(points_frame: &PointsFrame) -> Result<Self> {
+        let points: Vec<[f32; 3]> = points_frame
+            .vertices()?
+            .iter()
+            .map(|vertex| vertex.xyz)
+            .collect();
+
+        let Resolution { width, height } = points_frame.points_resolution()?;
+
+        Ok(Self {
+            width,
+            height,
+            points,
+            colors: None,
+            intensities: None,
+        })
+    }
+
+    /// Builds a buffer from a points frame, along with optional color and infrared frames.
+    ///
+    /// Colors are sampled from the color frame by texture coordinates, so the point cloud
+    /// must be computed after calling [PointCloud::map_to](crate::processing_block::PointCloud::map_to).
+    /// The infrared frame must have one pixel per point, which holds for infrared streams
+    /// with the same resolution as the depth stream.
+    pub fn from_frames(
+        points_frame: &PointsFrame,
+        color_frame: Option<&VideoFrame>,
+        infrared_frame: Option<&VideoFrame>,
+    ) -> Result<Self> {
+        let mut buffer = Self::from_points_frame(points_frame)?;
+        if let Some(color_frame) = color_frame {
+            buffer.colors = Some(sample_colors(points_frame, color_frame)?);
+        }
+        if let Some(infrared_frame) = infrared_frame {
+            buffer.intensities = Some(sample_intensities(infrared_frame, buffer.points.len())?);
+        }
+        Ok(buffer)
+    }
+
+    /// Returns the number of points.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Checks if the buffer contains no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Reads a PCD file from a path.
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = BufReader::new(File::open(path).map_err(Error::Io)?);
+        Self::read(reader)
+    }
+
+    /// Writes the buffer to a PCD file at path.
+    pub fn save<P>(&self, path: P, format: DataFormat) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let mut writer = BufWriter::new(File::create(path).map_err(Error::Io)?);
+        self.write(&mut writer, format)?;
+        writer.flush().map_err(Error::Io)?;
+        Ok(())
+    }
+
+    /// Serializes the buffer in PCD format.
+    pub fn write<W>(&self, writer: W, format: DataFormat) -> Result<()>
+    where
+        W: Write,
+    {
+        self.check_lengths()?;
+        self.write_unchecked(writer, format).map_err(Error::Io)
+    }
+
+    fn write_unchecked<W>(&self, mut writer: W, format: DataFormat) -> io::Result<()>
+    where
+        W: Write,
+    {
+        let mut fields = vec!["x", "y", "z"];
+        if self.colors.is_some() {
+            fields.push("rgb");
+        }
+        if self.intensities.is_some() {
+            fields.push("intensity");
+        }
+        let n_fields = fields.len();
+        let repeat = |value: &str| vec![value; n_fields].join(" ");
+
+        writeln!(writer, "# .PCD v0.7 - Point Cloud Data file format")?;
+        writeln!(writer, "VERSION 0.7")?;
+        writeln!(writer, "FIELDS {}", fields.join(" "))?;
+        writeln!(writer, "SIZE {}", repeat("4"))?;
+        writeln!(writer, "TYPE {}", repeat("F"))?;
+        writeln!(writer, "COUNT {}", repeat("1"))?;
+        writeln!(writer, "WIDTH {}", self.width)?;
+        writeln!(writer, "HEIGHT {}", self.height)?;
+        writeln!(writer, "VIEWPOINT 0 0 0 1 0 0 0")?;
+        writeln!(writer, "POINTS {}", self.len())?;
+        writeln!(writer, "DATA {}", format.as_str())?;
+
+        match format {
+            DataFormat::Ascii => {
+                for index in 0..self.len() {
+                    let [x, y, z] = self.points[index];
+                    write!(writer, "{} {} {}", x, y, z)?;
+                    if let Some(colors) = &self.colors {
+                        // PCL writes packed colors as integers in ASCII data
+                        write!(writer, " {}", pack_rgb(colors[index]).to_bits())?;
+                    }
+                    if let Some(intensities) = &self.intensities {
+                        write!(writer, " {}", intensities[index])?;
+                    }
+                    writeln!(writer)?;
+                }
+            }
+            DataFormat::Binary => {
+                let mut bytes = Vec::with_capacity(self.len() * n_fields * 4);
+                for index in 0..self.len() {
+                    for value in self.points[index].iter() {
+                        bytes.extend_from_slice(&value.to_le_bytes());
+                    }
+                    if let Some(colors) = &self.colors {
+                        bytes.extend_from_slice(&pack_rgb(colors[index]).to_le_bytes());
+                    }
+                    if let Some(intensities) = &self.intensities {
+                        bytes.extend_from_slice(&intensities[index].to_le_bytes());
+                    }
+                }
+                writer.write_all(&bytes)?;
+            }
+            DataFormat::BinaryCompressed => {
+                // fields are stored one after another instead of interleaved
+                let mut bytes = Vec::with_capacity(self.len() * n_fields * 4);
+                for axis in 0..3 {
+                    for point in self.points.iter() {
+                        bytes.extend_from_slice(&point[axis].to_le_bytes());
+                    }
+                }
+                if let Some(colors) = &self.colors {
+                    for &color in colors.iter() {
+                        bytes.extend_from_slice(&pack_rgb(color).to_le_bytes());
+                    }
+                }
+                if let Some(intensities) = &self.intensities {
+                    for intensity in intensities.iter() {
+                        bytes.extend_from_slice(&intensity.to_le_bytes());
+                    }
+                }
+
+                let compressed = lzf::compress(&bytes);
+                writer.write_all(&(compressed.len() as u32).to_le_bytes())?;
+                writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+                writer.write_all(&compressed)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parses a PCD file.
+    ///
+    /// Fields other than `x`, `y`, `z`, `rgb`, `rgba` and `intensity` are skipped.
+    pub fn read<R>(mut reader: R) -> Result<Self>
+    where
+        R: BufRead,
+    {
+        let header = Header::read(&mut reader)?;
+        let n_points = header.points;
+        let point_size = header.point_size()?;
+        let data_size = point_size
+            .checked_mul(n_points)
+            .ok_or_else(|| Error::InvalidData("the data size overflows".into()))?;
+
+        let x_field = header.field_index("x")?;
+        let y_field = header.field_index("y")?;
+        let z_field = header.field_index("z")?;
+        let rgb_field = header
+            .find_field("rgb")
+            .or_else(|| header.find_field("rgba"));
+        let intensity_field = header.find_field("intensity");
+
+        // binary data is read before allocating points, so that a malformed header cannot
+        // allocate more memory than the input contains
+        let bytes = match header.data {
+            DataFormat::Ascii => None,
+            DataFormat::Binary => Some(read_bytes(&mut reader, data_size)?),
+            DataFormat::BinaryCompressed => {
+                let sizes = read_bytes(&mut reader, 8)?;
+                let compressed_size =
+                    u32::from_le_bytes([sizes[0], sizes[1], sizes[2], sizes[3]]) as usize;
+                let uncompressed_size =
+                    u32::from_le_bytes([sizes[4], sizes[5], sizes[6], sizes[7]]) as usize;
+                if uncompressed_size != data_size {
+                    return Err(Error::InvalidData(format!(
+                        "expect {} bytes of uncompressed data, but found {} bytes",
+                        data_size, uncompressed_size
+                    )));
+                }
+
+                let compressed = read_bytes(&mut reader, compressed_size)?;
+                let bytes = lzf::decompress(&compressed, uncompressed_size)?;
+                if bytes.len() != data_size {
+                    return Err(Error::InvalidData(format!(
+                        "expect {} bytes of uncompressed data, but found {} bytes",
+                        data_size,
+                        bytes.len()
+                    )));
+                }
+                Some(bytes)
+            }
+        };
+        let capacity = if bytes.is_some() { n_points } else { 0 };
+
+        let mut buffer = Self {
+            width: header.width,
+            height: header.height,
+            points: Vec::with_capacity(capacity),
+            colors: rgb_field.map(|_| Vec::with_capacity(capacity)),
+            intensities: intensity_field.map(|_| Vec::with_capacity(capacity)),
+        };
+
+        let mut push_point = |values: &[Value]| {
+            buffer.points.push([
+                values[x_field].to_f32(),
+                values[y_field].to_f32(),
+                values[z_field].to_f32(),
+            ]);
+            if let (Some(colors), Some(index)) = (&mut buffer.colors, rgb_field) {
+                colors.push(unpack_rgb(values[index].to_bits()));
+            }
+            if let (Some(intensities), Some(index)) = (&mut buffer.intensities, intensity_field) {
+                intensities.push(values[index].to_f32());
+            }
+        };
+
+        let mut values = Vec::with_capacity(header.fields.len());
+        match (header.data, bytes) {
+            (DataFormat::Ascii, _) => {
+                let mut line = String::new();
+                let mut count = 0;
+                while count < n_points {
+                    line.clear();
+                    if reader.read_line(&mut line).map_err(Error::Io)? == 0 {
+                        return Err(Error::InvalidData(format!(
+                            "expect {} points, but found {} points",
+                            n_points, count
+                        )));
+                    }
+                    let mut tokens = line.split_whitespace().peekable();
+                    if tokens.peek().is_none() {
+                        continue;
+                    }
+
+                    values.clear();
+                    for field in header.fields.iter() {
+                        let token = tokens.next().ok_or_else(|| {
+                            Error::InvalidData(format!("missing values at point {}", count))
+                        })?;
+                        values.push(field.parse(token)?);
+                        // only the first element of a field is kept
+                        for _ in 1..field.count {
+                            if tokens.next().is_none() {
+                                break;
+                            }
+                        }
+                    }
+                    push_point(&values);
+                    count += 1;
+                }
+            }
+            (DataFormat::Binary, Some(bytes)) => {
+                for point_bytes in bytes.chunks_exact(point_size) {
+                    values.clear();
+                    let mut offset = 0;
+                    for field in header.fields.iter() {
+                        values.push(field.decode(&point_bytes[offset..]));
+                        offset += field.size * field.count;
+                    }
+                    push_point(&values);
+                }
+            }
+            (DataFormat::BinaryCompressed, Some(bytes)) => {
+                // each field is stored contiguously for all points
+                let mut field_offsets = Vec::with_capacity(header.fields.len());
+                let mut offset = 0;
+                for field in header.fields.iter() {
+                    field_offsets.push(offset);
+                    offset += field.size * field.count * n_points;
+                }
+
+                for index in 0..n_points {
+                    values.clear();
+                    for (field, &field_offset) in header.fields.iter().zip(field_offsets.iter()) {
+                        let begin = field_offset + index * field.size * field.count;
+                        values.push(field.decode(&bytes[begin..]));
+                    }
+                    push_point(&values);
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(buffer)
+    }
+
+    fn check_lengths(&self) -> Result<()> {
+        let len = self.len();
+        if self.width.checked_mul(self.height) != Some(len) {
+            return Err(Error::InvalidData(format!(
+                "width {} and height {} do not match the number of points {}",
+                self.width, self.height, len
+            )));
+        }
+        if matches!(&self.colors, Some(colors) if colors.len() != len) {
+            return Err(Error::InvalidData(
+                "the number of colors does not match the number of points".into(),
+            ));
+        }
+        if matches!(&self.intensities, Some(intensities) if intensities.len() != len) {
+            return Err(Error::InvalidData(
+                "the number of intensities does not match the number of points".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Packs a color into a float in the way PCL stores `rgb` fields.
+fn pack_rgb([r, g, b]: [u8; 3]) -> f32 {
+    f32::from_bits(((r as u32) << 16) | ((g as u32) << 8) | b as u32)
+}
+
+fn unpack_rgb(bits: u32) -> [u8; 3] {
+    [(bits >> 16) as u8, (bits >> 8) as u8, bits as u8]
+}
+
+fn sample_colors(points_frame: &PointsFrame, color_frame: &VideoFrame) -> Result<Vec<[u8; 3]>> {
+    let StreamProfileData { format, .. } = color_frame.stream_profile()?.get_data()?;
+    let (bytes_per_pixel, rgb_indices) = match format {
+        Format::Rgb8 => (3, [0, 1, 2]),
+        Format::Bgr8 => (3, [2, 1, 0]),
+        Format::Rgba8 => (4, [0, 1, 2]),
+        Format::Bgra8 => (4, [2, 1, 0]),
+        _ => {
+            return Err(Error::InvalidData(format!(
+                "unsupported color format {:?}",
+                format
+            )))
+        }
+    };
+
+    let pixel_data = color_frame.data()?;
+    let Resolution { width, height } = color_frame.resolution()?;
+    let stride = color_frame.stride_in_bytes()?;
+    let [r_index, g_index, b_index] = rgb_indices;
+
+    let colors = points_frame
+        .texture_coordinates()?
+        .iter()
+        .map(|tex_coord| {
+            let (u, v) = (tex_coord.u, tex_coord.v);
+            if (0.0..1.0).contains(&u) && (0.0..1.0).contains(&v) {
+                let x = (u * width as f32) as usize;
+                let y = (v * height as f32) as usize;
+                let offset = x * bytes_per_pixel + y * stride;
+                [
+                    pixel_data[offset + r_index],
+                    pixel_data[offset + g_index],
+                    pixel_data[offset + b_index],
+                ]
+            } else {
+                [0, 0, 0]
+            }
+        })
+        .collect();
+    Ok(colors)
+}
+
+fn sample_intensities(infrared_frame: &VideoFrame, n_points: usize) -> Result<Vec<f32>> {
+    let StreamProfileData { format, .. } = infrared_frame.stream_profile()?.get_data()?;
+    let Resolution { width, height } = infrared_frame.resolution()?;
+    if width * height != n_points {
+        return Err(Error::InvalidData(format!(
+            "infrared resolution {}x{} does not match the number of points {}",
+            width, height, n_points
+        )));
+    }
+
+    let data = infrared_frame.data()?;
+    let stride = infrared_frame.stride_in_bytes()?;
+    let rows = data.chunks_exact(stride).take(height);
+
+    let intensities = match format {
+        Format::Y8 => rows
+            .flat_map(|row| row[..width].iter().map(|&value| value as f32))
+            .collect(),
+        Format::Y16 => rows
+            .flat_map(|row| {
+                row[..width * 2]
+                    .chunks_exact(2)
+                    .map(|bytes| u16::from_ne_bytes([bytes[0], bytes[1]]) as f32)
+            })
+            .collect(),
+        _ => {
+            return Err(Error::InvalidData(format!(
+                "unsupported infrared format {:?}",
+                format
+            )))
+        }
+    };
+    Ok(intensities)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldType {
+    Float,
+    Signed,
+    Unsigned,
+}
+
+#[derive(Debug, Clone)]
+struct Field {
+    name: String,
+    size: usize,
+    type_: FieldType,
+    count: usize,
+}
+
+impl Field {
+    /// Decodes the first element of the field from little-endian bytes.
+    fn decode(&self, bytes: &[u8]) -> Value {
+        let mut buf = [0u8; 8];
+        buf[..self.size].copy_from_slice(&bytes[..self.size]);
+        match (self.type_, self.size) {
+            (FieldType::Float, 4) => {
+                Value::Float32(f32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]))
+            }
+            (FieldType::Float, _) => Value::Float64(f64::from_le_bytes(buf)),
+            (FieldType::Unsigned, _) => Value::Unsigned(u64::from_le_bytes(buf)),
+            (FieldType::Signed, size) => {
+                // sign-extend
+                let shift = 64 - size * 8;
+                Value::Signed((u64::from_le_bytes(buf) << shift) as i64 >> shift)
+            }
+        }
+    }
+
+    fn parse(&self, token: &str) -> Result<Value> {
+        // packed colors may be written as integers even if the field type is float
+        if self.type_ == FieldType::Float && (self.name == "rgb" || self.name == "rgba") {
+            if let Ok(bits) = token.parse::<u32>() {
+                return Ok(Value::Unsigned(bits as u64));
+            }
+        }
+
+        let invalid =
+            || Error::InvalidData(format!("invalid value '{}' in field {}", token, self.name));
+        let value = match (self.type_, self.size) {
+            (FieldType::Float, 4) => Value::Float32(token.parse().map_err(|_| invalid())?),
+            (FieldType::Float, _) => Value::Float64(token.parse().map_err(|_| invalid())?),
+            (FieldType::Unsigned, _) => Value::Unsigned(token.parse().map_err(|_| invalid())?),
+            (FieldType::Signed, _) => Value::Signed(token.parse().map_err(|_| invalid())?),
+        };
+        Ok(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Value {
+    Float32(f32),
+    Float64(f64),
+    Signed(i64),
+    Unsigned(u64),
+}
+
+impl Value {
+    fn to_f32(self) -> f32 {
+        match self {
+            Self::Float32(value) => value,
+            Self::Float64(value) => value as f32,
+            Self::Signed(value) => value as f32,
+            Self::Unsigned(value) => value as f32,
+        }
+    }
+
+    /// Reinterprets the value as packed color bits.
+    fn to_bits(self) -> u32 {
+        match self {
+            Self::Float32(value) => value.to_bits(),
+            Self::Float64(value) => (value as f32).to_bits(),
+            Self::Signed(value) => value as u32,
+            Self::Unsigned(value) => value as u32,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Header {
+    fields: Vec<Field>,
+    width: usize,
+    height: usize,
+    points: usize,
+    data: DataFormat,
+}
+
+impl Header {
+    fn read<R>(reader: &mut R) -> Result<Self>
+    where
+        R: BufRead,
+    {
+        let mut names: Option<Vec<String>> = None;
+        let mut sizes: Option<Vec<usize>> = None;
+        let mut types: Option<Vec<FieldType>> = None;
+        let mut counts: Option<Vec<usize>> = None;
+        let mut width: Option<usize> = None;
+        let mut height: Option<usize> = None;
+        let mut points: Option<usize> = None;
+
+        let mut line = String::new();
+        let data = loop {
+            line.clear();
+            if reader.read_line(&mut line).map_err(Error::Io)? == 0 {
+                return Err(Error::InvalidData("missing DATA entry in header".into()));
+            }
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            let key = tokens.next().unwrap().to_ascii_uppercase();
+            let values: Vec<&str> = tokens.collect();
+
+            match key.as_str() {
+                "VERSION" | "VIEWPOINT" => {}
+                "FIELDS" | "COLUMNS" => {
+                    names = Some(values.iter().map(|name| name.to_string()).collect());
+                }
+                "SIZE" => {
+                    sizes = Some(
+                        values
+                            .iter()
+                            .map(|value| match parse_usize(value)? {
+                                size @ 1 | size @ 2 | size @ 4 | size @ 8 => Ok(size),
+                                size => {
+                                    Err(Error::InvalidData(format!("invalid field size {}", size)))
+                                }
+                            })
+                            .collect::<Result<_>>()?,
+                    );
+                }
+                "TYPE" => {
+                    types = Some(
+                        values
+                            .iter()
+                            .map(|value| match *value {
+                                "F" => Ok(FieldType::Float),
+                                "I" => Ok(FieldType::Signed),
+                                "U" => Ok(FieldType::Unsigned),
+                                _ => Err(Error::InvalidData(format!(
+                                    "invalid field type '{}'",
+                                    value
+                                ))),
+                            })
+                            .collect::<Result<_>>()?,
+                    );
+                }
+                "COUNT" => {
+                    counts = Some(
+                        values
+                            .iter()
+                            .map(|value| match parse_usize(value)? {
+                                0 => Err(Error::InvalidData("invalid field count 0".into())),
+                                count => Ok(count),
+                            })
+                            .collect::<Result<_>>()?,
+                    );
+                }
+                "WIDTH" => width = Some(parse_single(&key, &values)?),
+                "HEIGHT" => height = Some(parse_single(&key, &values)?),
+                "POINTS" => points = Some(parse_single(&key, &values)?),
+                "DATA" => {
+                    let data = match values.first().copied() {
+                        Some("ascii") => DataFormat::Ascii,
+                        Some("binary") => DataFormat::Binary,
+                        Some("binary_compressed") => DataFormat::BinaryCompressed,
+                        _ => {
+                            return Err(Error::InvalidData(format!(
+                                "unsupported DATA entry '{}'",
+                                values.join(" ")
+                            )))
+                        }
+                    };
+                    break data;
+                }
+                _ => {
+                    return Err(Error::InvalidData(format!(
+                        "unknown header entry '{}'",
+                        key
+                    )))
+                }
+            }
+        };
+
+        let names =
+            names.ok_or_else(|| Error::InvalidData("missing FIELDS entry in header".into()))?;
+        let sizes =
+            sizes.ok_or_else(|| Error::InvalidData("missing SIZE entry in header".into()))?;
+        let types =
+            types.ok_or_else(|| Error::InvalidData("missing TYPE entry in header".into()))?;
+        let counts = counts.unwrap_or_else(|| vec![1; names.len()]);
+        if sizes.len() != names.len() || types.len() != names.len() || counts.len() != names.len() {
+            return Err(Error::InvalidData(
+                "FIELDS, SIZE, TYPE and COUNT entries have different lengths".into(),
+            ));
+        }
+
+        let fields = names
+            .into_iter()
+            .zip(sizes)
+            .zip(types)
+            .zip(counts)
+            .map(|(((name, size), type_), count)| {
+                if type_ == FieldType::Float && size != 4 && size != 8 {
+                    return Err(Error::InvalidData(format!(
+                        "invalid size {} for float field {}",
+                        size, name
+                    )));
+                }
+                Ok(Field {
+                    name,
+                    size,
+                    type_,
+                    count,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let width =
+            width.ok_or_else(|| Error::InvalidData("missing WIDTH entry in header".into()))?;
+        let height = height.unwrap_or(1);
+        let size = width.checked_mul(height);
+        let points = match (points, size) {
+            (Some(points), _) => points,
+            (None, Some(size)) => size,
+            (None, None) => {
+                return Err(Error::InvalidData(format!(
+                    "width {} and height {} overflow",
+                    width, height
+                )))
+            }
+        };
+        if size != Some(points) {
+            return Err(Error::InvalidData(format!(
+                "width {} and height {} do not match the number of points {}",
+                width, height, points
+            )));
+        }
+
+        Ok(Self {
+            fields,
+            width,
+            height,
+            points,
+            data,
+        })
+    }
+
+    fn find_field(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|field| field.name == name)
+    }
+
+    fn field_index(&self, name: &str) -> Result<usize> {
+        self.find_field(name)
+            .ok_or_else(|| Error::InvalidData(format!("missing field {}", name)))
+    }
+
+    fn point_size(&self) -> Result<usize> {
+        self.fields
+            .iter()
+            .try_fold(0usize, |sum, field| {
+                field
+                    .size
+                    .checked_mul(field.count)
+                    .and_then(|size| sum.checked_add(size))
+            })
+            .ok_or_else(|| Error::InvalidData("the point size overflows".into()))
+    }
+}
+
+/// Reads exactly `len` bytes without allocating more than the input contains.
+fn read_bytes<R>(reader: R, len: usize) -> Result<Vec<u8>>
+where
+    R: Read,
+{
+    let mut bytes = vec![];
+    reader
+        .take(len as u64)
+        .read_to_end(&mut bytes)
+        .map_err(Error::Io)?;
+    if bytes.len() != len {
+        return Err(Error::InvalidData(format!(
+            "expect {} bytes of data, but found {} bytes",
+            len,
+            bytes.len()
+        )));
+    }
+    Ok(bytes)
+}
+
+fn parse_usize(token: &str) -> Result<usize> {
+    token
+        .parse()
+        .map_err(|_| Error::InvalidData(format!("invalid integer '{}' in header", token)))
+}
+
+fn parse_single(key: &str, values: &[&str]) -> Result<usize> {
+    match values {
+        [value] => parse_usize(value),
+        _ => Err(Error::InvalidData(format!(
+            "expect exactly one value for {}",
+            key
+        ))),
+    }
+}
+
+/// The LZF compression used by `binary_compressed` PCD data.
+mod lzf {
+    use super::*;
+
+    const HASH_LOG: usize = 14;
+    const MAX_LITERAL: usize = 32;
+    const MAX_OFFSET: usize = 1 << 13;
+    const MAX_MATCH: usize = 264;
+
+    pub fn compress(input: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(input.len() / 2 + 16);
+        let mut table = vec![usize::MAX; 1 << HASH_LOG];
+        let mut literal_begin = 0;
+        let mut index = 0;
+
+        while index + 2 < input.len() {
+            let hash = {
+                let value = (input[index] as usize) << 16
+                    | (input[index + 1] as usize) << 8
+                    | input[index + 2] as usize;
+                (value.wrapping_mul(2654435761) >> 8) & ((1 << HASH_LOG) - 1)
+            };
+            let candidate = table[hash];
+            table[hash] = index;
+
+            let is_match = candidate != usize::MAX
+                && index - candidate <= MAX_OFFSET
+                && input[candidate..candidate + 3] == input[index..index + 3];
+            if !is_match {
+                index += 1;
+                continue;
+            }
+
+            flush_literals(&mut output, &input[literal_begin..index]);
+
+            let max_len = (input.len() - index).min(MAX_MATCH);
+            let mut len = 3;
+            while len < max_len && input[candidate + len] == input[index + len] {
+                len += 1;
+            }
+
+            let offset = index - candidate - 1;
+            let encoded_len = len - 2;
+            if encoded_len < 7 {
+                output.push(((encoded_len << 5) | (offset >> 8)) as u8);
+            } else {
+                output.push(((7 << 5) | (offset >> 8)) as u8);
+                output.push((encoded_len - 7) as u8);
+            }
+            output.push(offset as u8);
+
+            index += len;
+            literal_begin = index;
+        }
+
+        flush_literals(&mut output, &input[literal_begin..]);
+        output
+    }
+
+    pub fn decompress(input: &[u8], output_size: usize) -> Result<Vec<u8>> {
+        let corrupted = || Error::InvalidData("corrupted LZF compressed data".into());
+        // a 3-byte back reference expands to at most MAX_MATCH bytes
+        let max_size = input.len().saturating_mul(MAX_MATCH / 3 + 1);
+        let mut output = Vec::with_capacity(output_size.min(max_size));
+        let mut index = 0;
+
+        while index < input.len() {
+            let ctrl = input[index] as usize;
+            index += 1;
+
+            if ctrl < MAX_LITERAL {
+                let len = ctrl + 1;
+                let literal = input.get(index..index + len).ok_or_else(corrupted)?;
+                output.extend_from_slice(literal);
+                index += len;
+            } else {
+                let mut len = ctrl >> 5;
+                if len == 7 {
+                    len += *input.get(index).ok_or_else(corrupted)? as usize;
+                    index += 1;
+                }
+                let offset =
+                    ((ctrl & 0x1f) << 8) + *input.get(index).ok_or_else(corrupted)? as usize + 1;
+                index += 1;
+
+                let begin = output.len().checked_sub(offset).ok_or_else(corrupted)?;
+                // the referenced range may overlap with the bytes being written
+                for pos in begin..begin + len + 2 {
+                    let byte = output[pos];
+                    output.push(byte);
+                }
+            }
+
+            if output.len() > output_size {
+                return Err(corrupted());
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn flush_literals(output: &mut Vec<u8>, literals: &[u8]) {
+        for chunk in literals.chunks(MAX_LITERAL) {
+            output.push((chunk.len() - 1) as u8);
+            output.extend_from_slice(chunk);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn sample_buffer() -> PointBuffer {
+        PointBuffer {
+            width: 2,
+            height: 2,
+            points: vec![
+                [0.0, 0.5, 1.0],
+                [-1.25, 2.0, 3.5],
+                [1e-3, -7.0, 0.125],
+                [f32::MAX, f32::MIN_POSITIVE, -0.0],
+            ],
+            colors: Some(vec![[255, 0, 0], [0, 255, 0], [0, 0, 255], [12, 34, 56]]),
+            intensities: Some(vec![0.0, 1.0, 255.0, 65535.0]),
+        }
+    }
+
+    fn round_trip(buffer: &PointBuffer, format: DataFormat) -> PointBuffer {
+        let mut bytes = vec![];
+        buffer.write(&mut bytes, format).unwrap();
+        PointBuffer::read(Cursor::new(bytes)).unwrap()
+    }
+
+    #[test]
+    fn ascii_round_trip() {
+        let buffer = sample_buffer();
+        assert_eq!(round_trip(&buffer, DataFormat::Ascii), buffer);
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let buffer = sample_buffer();
+        assert_eq!(round_trip(&buffer, DataFormat::Binary), buffer);
+    }
+
+    #[test]
+    fn binary_compressed_round_trip() {
+        let buffer = sample_buffer();
+        assert_eq!(round_trip(&buffer, DataFormat::BinaryCompressed), buffer);
+    }
+
+    #[test]
+    fn round_trip_without_channels() {
+        let buffer = PointBuffer {
+            width: 3,
+            height: 1,
+            points: vec![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [7.0, 8.0, 9.0]],
+            colors: None,
+            intensities: None,
+        };
+        for &format in [
+            DataFormat::Ascii,
+            DataFormat::Binary,
+            DataFormat::BinaryCompressed,
+        ]
+        .iter()
+        {
+            assert_eq!(round_trip(&buffer, format), buffer);
+        }
+    }
+
+    #[test]
+    fn lzf_round_trip() {
+        let input: Vec<u8> = (0..10000).map(|index| (index % 7) as u8).collect();
+        let compressed = lzf::compress(&input);
+        assert!(compressed.len() < input.len());
+        assert_eq!(lzf::decompress(&compressed, input.len()).unwrap(), input);
+    }
+
+    #[test]
+    fn lzf_incompressible_round_trip() {
+        let mut state = 0x1234_5678u32;
+        let input: Vec<u8> = (0..4096)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 24) as u8
+            })
+            .collect();
+        let compressed = lzf::compress(&input);
+        assert_eq!(lzf::decompress(&compressed, input.len()).unwrap(), input);
+    }
+
+    #[test]
+    fn lzf_empty_round_trip() {
+        let compressed = lzf::compress(&[]);
+        assert!(compressed.is_empty());
+        assert!(lzf::decompress(&compressed, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn lzf_rejects_corrupted_data() {
+        // a back reference before any output
+        assert!(lzf::decompress(&[0x20, 0x00], 16).is_err());
+        // a literal run longer than the input
+        assert!(lzf::decompress(&[0x05, 0x01], 16).is_err());
+    }
+
+    #[test]
+    fn reject_overflowing_header() {
+        let header = "FIELDS x y z\nSIZE 4 4 4\nTYPE F F F\n\
+                      WIDTH 18446744073709551615\nHEIGHT 2\nDATA binary\n";
+        assert!(PointBuffer::read(Cursor::new(header)).is_err());
+
+        let header = "FIELDS x y z\nSIZE 4 4 4\nTYPE F F F\nCOUNT 1 1 18446744073709551615\n\
+                      WIDTH 2\nHEIGHT 1\nDATA binary\n";
+        assert!(PointBuffer::read(Cursor::new(header)).is_err());
+    }
+
+    #[test]
+    fn reject_truncated_data() {
+        let header = "FIELDS x y z\nSIZE 4 4 4\nTYPE F F F\nWIDTH 1000000000\nDATA binary\n";
+        assert!(PointBuffer::read(Cursor::new(header)).is_err());
+
+        let mut bytes =
+            b"FIELDS x y z\nSIZE 4 4 4\nTYPE F F F\nWIDTH 1\nDATA binary_compressed\n".to_vec();
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(&12u32.to_le_bytes());
+        assert!(PointBuffer::read(Cursor::new(bytes)).is_err());
+
+        let header = "FIELDS x y z\nSIZE 4 4 4\nTYPE F F F\nWIDTH 1000000000\nDATA ascii\n1 2 3\n";
+        assert!(PointBuffer::read(Cursor::new(header)).is_err());
+    }
+}
@@ -2,14 +2,31 @@
 
 use crate::{
     base::DEFAULT_TIMEOUT,
+    blocking,
+    callback::new_frame_callback,
     common::*,
     config::Config,
     context::Context,
     error::{Error as RsError, ErrorChecker, Result},
-    frame::{CompositeFrame, Frame, GenericFrameEx},
+    frame::{AnyFrame, CompositeFrame, Frame, GenericFrameEx},
     pipeline_kind::{self, PipelineState},
     pipeline_profile::PipelineProfile,
+    wait_future::WaitFuture,
 };
+use futures::{stream::Stream, task::AtomicWaker};
+use std::collections::VecDeque;
+
+/// The interval to check if the worker of [PipelineFrameStream] should stop.
+const STREAM_POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The policy to drop frames when the buffer of [PipelineFrameStream] is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DropPolicy {
+    /// Drops the oldest buffered frame to make room for the new one.
+    DropOldest,
+    /// Drops the new frame and keeps the buffered ones.
+    DropNewest,
+}
 
 /// Represents the data pipeline from a RealSense device.
 #[derive(Debug)]
@@ -17,11 +34,20 @@ pub struct Pipeline<State>
 where
     State: pipeline_kind::PipelineState,
 {
-    pub(crate) ptr: NonNull<sys::rs2_pipeline>,
+    handle: Arc<PipelineHandle>,
     context: Context,
     state: State,
 }
 
+/// The owned pipeline pointer, shared with the background workers of the pipeline.
+///
+/// The librealsense pipeline keeps its own reference to the context, so it may outlive
+/// the [Context] wrapper.
+#[derive(Debug)]
+struct PipelineHandle {
+    ptr: NonNull<sys::rs2_pipeline>,
+}
+
 // type aliases
 
 pub type InactivePipeline = Pipeline<pipeline_kind::Inactive>;
@@ -46,7 +72,9 @@ impl InactivePipeline {
         };
 
         let pipeline = Self {
-            ptr: NonNull::new(ptr).unwrap(),
+            handle: Arc::new(PipelineHandle {
+                ptr: NonNull::new(ptr).unwrap(),
+            }),
             context,
             state: pipeline_kind::Inactive,
         };
@@ -62,7 +90,7 @@ impl InactivePipeline {
             Some(conf) => unsafe {
                 let mut checker = ErrorChecker::new();
                 let ptr = sys::rs2_pipeline_start_with_config(
-                    self.ptr.as_ptr(),
+                    self.ptr().as_ptr(),
                     conf.ptr.as_ptr(),
                     checker.inner_mut_ptr(),
                 );
@@ -71,22 +99,67 @@ impl InactivePipeline {
             },
             None => unsafe {
                 let mut checker = ErrorChecker::new();
-                let ptr = sys::rs2_pipeline_start(self.ptr.as_ptr(), checker.inner_mut_ptr());
+                let ptr = sys::rs2_pipeline_start(self.ptr().as_ptr(), checker.inner_mut_ptr());
                 checker.check()?;
                 ptr
             },
         };
 
         let profile = unsafe { PipelineProfile::from_raw(ptr) };
-        let pipeline = {
-            let (pipeline_ptr, context_ptr) = self.into_raw_parts();
-            Pipeline {
-                ptr: NonNull::new(pipeline_ptr).unwrap(),
-                context: unsafe { Context::from_raw(context_ptr) },
-                state: pipeline_kind::Active { profile },
-            }
+        let pipeline = self.into_state(pipeline_kind::Active {
+            profile,
+            callback: None,
+        });
+        Ok(pipeline)
+    }
+
+    /// Start the pipeline with an optional config, delivering frames to the callback.
+    ///
+    /// The callback is called on the librealsense thread as soon as frames arrive,
+    /// usually with a [CompositeFrame]. The [wait](Pipeline::wait) family of methods
+    /// fails on the pipeline started this way. The callback is dropped after the pipeline
+    /// is stopped or dropped.
+    pub fn start_with_callback<'a, F>(
+        self,
+        config: impl Into<Option<&'a Config>>,
+        callback: F,
+    ) -> Result<ActivePipeline>
+    where
+        F: FnMut(AnyFrame) + Send + 'static,
+    {
+        let config = config.into();
+        let (callback, context) = new_frame_callback(callback);
+        let ptr = match config {
+            Some(conf) => unsafe {
+                let mut checker = ErrorChecker::new();
+                let ptr = sys::rs2_pipeline_start_with_config_and_callback(
+                    self.ptr().as_ptr(),
+                    conf.ptr.as_ptr(),
+                    callback,
+                    context.as_ptr(),
+                    checker.inner_mut_ptr(),
+                );
+                checker.check()?;
+                ptr
+            },
+            None => unsafe {
+                let mut checker = ErrorChecker::new();
+                let ptr = sys::rs2_pipeline_start_with_callback(
+                    self.ptr().as_ptr(),
+                    callback,
+                    context.as_ptr(),
+                    checker.inner_mut_ptr(),
+                );
+                checker.check()?;
+                ptr
+            },
         };
 
+        let profile = unsafe { PipelineProfile::from_raw(ptr) };
+        let pipeline = self.into_state(pipeline_kind::Active {
+            profile,
+            callback: Some(context),
+        });
         Ok(pipeline)
     }
 
@@ -96,18 +169,18 @@ impl InactivePipeline {
         config: impl Into<Option<&'a Config>>,
     ) -> Result<ActivePipeline> {
         let config = config.into();
-        let pipeline_ptr = AtomicPtr::new(self.ptr.as_ptr());
+        let handle = self.handle.clone();
         let config_ptr_opt = config.map(|conf| AtomicPtr::new(conf.ptr.as_ptr()));
         let (tx, rx) = futures::channel::oneshot::channel();
 
-        // start blocking thread
-        thread::spawn(move || {
+        // start blocking job
+        blocking::spawn_blocking(move || {
             let func = || unsafe {
                 let profile_ptr = match config_ptr_opt {
                     Some(config_ptr) => {
                         let mut checker = ErrorChecker::new();
                         let ptr = sys::rs2_pipeline_start_with_config(
-                            pipeline_ptr.load(Ordering::SeqCst),
+                            handle.ptr.as_ptr(),
                             config_ptr.load(Ordering::SeqCst),
                             checker.inner_mut_ptr(),
                         );
@@ -116,10 +189,8 @@ impl InactivePipeline {
                     }
                     None => {
                         let mut checker = ErrorChecker::new();
-                        let ptr = sys::rs2_pipeline_start(
-                            pipeline_ptr.load(Ordering::SeqCst),
-                            checker.inner_mut_ptr(),
-                        );
+                        let ptr =
+                            sys::rs2_pipeline_start(handle.ptr.as_ptr(), checker.inner_mut_ptr());
                         checker.check()?;
                         ptr
                     }
@@ -132,15 +203,10 @@ impl InactivePipeline {
 
         let profile_ptr = rx.await.unwrap()?;
         let profile = unsafe { PipelineProfile::from_raw(profile_ptr.load(Ordering::SeqCst)) };
-        let pipeline = {
-            let (pipeline_ptr, context_ptr) = self.into_raw_parts();
-            Pipeline {
-                ptr: NonNull::new(pipeline_ptr).unwrap(),
-                context: unsafe { Context::from_raw(context_ptr) },
-                state: pipeline_kind::Active { profile },
-            }
-        };
-
+        let pipeline = self.into_state(pipeline_kind::Active {
+            profile,
+            callback: None,
+        });
         Ok(pipeline)
     }
 
@@ -148,8 +214,8 @@ impl InactivePipeline {
     ///
     /// It returns the raw pointer along with the context pointer that the pipeline depends on.
     pub fn into_raw_parts(self) -> (*mut sys::rs2_pipeline, *mut sys::rs2_context) {
-        // take fields without invoking drop()
-        let ptr = self.ptr;
+        // take fields without invoking drop(), and leak the handle to keep the pointer alive
+        let ptr = self.ptr();
         let context = unsafe { self.context.unsafe_clone().into_raw() };
         mem::forget(self);
         (ptr.as_ptr(), context)
@@ -164,7 +230,9 @@ impl InactivePipeline {
     ) -> Self {
         let context = Context::from_raw(context_ptr);
         Self {
-            ptr: NonNull::new(pipeline_ptr).unwrap(),
+            handle: Arc::new(PipelineHandle {
+                ptr: NonNull::new(pipeline_ptr).unwrap(),
+            }),
             context,
             state: pipeline_kind::Inactive,
         }
@@ -191,7 +259,7 @@ impl ActivePipeline {
             let mut checker = ErrorChecker::new();
             let ptr = unsafe {
                 sys::rs2_pipeline_wait_for_frames(
-                    self.ptr.as_ptr(),
+                    self.ptr().as_ptr(),
                     timeout_ms,
                     checker.inner_mut_ptr(),
                 )
@@ -221,7 +289,7 @@ impl ActivePipeline {
             let mut checker = ErrorChecker::new();
             let mut ptr: *mut sys::rs2_frame = ptr::null_mut();
             let ret = sys::rs2_pipeline_poll_for_frames(
-                self.ptr.as_ptr(),
+                self.ptr().as_ptr(),
                 &mut ptr as *mut _,
                 checker.inner_mut_ptr(),
             );
@@ -247,69 +315,125 @@ impl ActivePipeline {
     /// or returns `Ok(None)` when timeout occurs.
     ///
     /// If the timeout is `None`, it waits indefinitely before the next frame.
-    pub async fn wait_async(
+    ///
+    /// Dropping the returned future stops the background wait.
+    pub fn wait_async(
         &mut self,
         timeout: impl Into<Option<Duration>>,
-    ) -> Result<Option<CompositeFrame>> {
-        let timeout = timeout.into();
-        let timeout_ms = timeout
-            .map(|duration| duration.as_millis() as c_uint)
-            .unwrap_or(sys::RS2_DEFAULT_TIMEOUT as c_uint);
-        let (tx, rx) = futures::channel::oneshot::channel();
-        let pipeline_ptr = AtomicPtr::new(self.ptr.as_ptr());
+    ) -> WaitFuture<Result<Option<CompositeFrame>>> {
+        let handle = self.handle.clone();
+
+        WaitFuture::new(timeout.into(), move |interval, is_last| {
+            let mut checker = ErrorChecker::new();
+            let ptr = unsafe {
+                sys::rs2_pipeline_wait_for_frames(
+                    handle.ptr.as_ptr(),
+                    interval.as_millis() as c_uint,
+                    checker.inner_mut_ptr(),
+                )
+            };
+            match checker.check() {
+                Err(RsError::Timeout(_)) if !is_last => None,
+                Err(RsError::Timeout(_)) => Some(Ok(None)),
+                result => Some(result.map(|_| Some(unsafe { Frame::from_raw(ptr) }))),
+            }
+        })
+    }
+
+    /// Creates a stream of frame sets.
+    ///
+    /// The frames are received by a single worker thread and buffered up to `capacity`
+    /// frames. When the buffer is full, frames are dropped according to `policy`.
+    /// The stream ends after yielding an error.
+    ///
+    /// Dropping the stream stops the worker and blocks until it leaves, which takes at most
+    /// 100 milliseconds, so that the worker does not take frames from later
+    /// [wait](Pipeline::wait) calls.
+    ///
+    /// The worker is a dedicated thread rather than a [blocking] waiter, and thus is not
+    /// counted or limited by [max_waiter_threads](blocking::max_waiter_threads).
+    pub fn frames(&mut self, capacity: usize, policy: DropPolicy) -> PipelineFrameStream<'_> {
+        let capacity = capacity.max(1);
+        let shared = Arc::new(StreamShared {
+            buffer: Mutex::new(VecDeque::with_capacity(capacity)),
+            waker: AtomicWaker::new(),
+            stopped: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+        });
+        let handle = self.handle.clone();
+
+        let worker = {
+            let shared = shared.clone();
+            thread::spawn(move || {
+                let timeout_ms = STREAM_POLL_TIMEOUT.as_millis() as c_uint;
 
-        thread::spawn(move || {
-            let result = unsafe {
-                loop {
+                while !shared.stopped.load(Ordering::SeqCst) {
                     let mut checker = ErrorChecker::new();
-                    let ptr = sys::rs2_pipeline_wait_for_frames(
-                        pipeline_ptr.load(Ordering::Relaxed),
-                        timeout_ms,
-                        checker.inner_mut_ptr(),
-                    );
-                    let result = match (timeout, checker.check()) {
-                        (None, Err(RsError::Timeout(_))) => continue,
-                        (Some(_), Err(RsError::Timeout(_))) => Ok(None),
-                        (_, result) => result.map(|_| Some(Frame::from_raw(ptr))),
+                    let ptr = unsafe {
+                        sys::rs2_pipeline_wait_for_frames(
+                            handle.ptr.as_ptr(),
+                            timeout_ms,
+                            checker.inner_mut_ptr(),
+                        )
+                    };
+                    let result = match checker.check() {
+                        Err(RsError::Timeout(_)) => continue,
+                        result => result.map(|_| unsafe { Frame::from_raw(ptr) }),
                     };
-                    break result;
+                    let is_err = result.is_err();
+
+                    {
+                        let mut buffer = shared.buffer.lock().unwrap();
+                        if buffer.len() >= capacity && !is_err {
+                            match policy {
+                                DropPolicy::DropOldest => {
+                                    buffer.pop_front();
+                                }
+                                DropPolicy::DropNewest => continue,
+                            }
+                        }
+                        buffer.push_back(result);
+                    }
+                    shared.waker.wake();
+
+                    if is_err {
+                        break;
+                    }
                 }
-            };
-            let _ = tx.send(result);
-        });
 
-        let frame = rx.await.unwrap()?;
-        Ok(frame)
+                shared.finished.store(true, Ordering::SeqCst);
+                shared.waker.wake();
+            })
+        };
+
+        PipelineFrameStream {
+            shared,
+            worker: Some(worker),
+            _phantom: PhantomData,
+        }
     }
 
     /// Stop the pipeline.
     ///
     /// This method consumes the pipeline instance and returns pipeline markered inactive.
-    pub fn stop(self) -> Result<InactivePipeline> {
+    pub fn stop(mut self) -> Result<InactivePipeline> {
         unsafe {
             let mut checker = ErrorChecker::new();
-            sys::rs2_pipeline_stop(self.ptr.as_ptr(), checker.inner_mut_ptr());
+            sys::rs2_pipeline_stop(self.ptr().as_ptr(), checker.inner_mut_ptr());
             checker.check()?;
         }
 
-        let pipeline = {
-            let (pipeline_ptr, context_ptr, profile_ptr) = self.into_raw_parts();
-
-            mem::drop(unsafe { pipeline_kind::Active::from_raw_parts(profile_ptr) });
+        // no more frames are delivered to the callback after stopping
+        mem::drop(self.state.callback.take());
 
-            Pipeline {
-                ptr: NonNull::new(pipeline_ptr).unwrap(),
-                context: unsafe { Context::from_raw(context_ptr) },
-                state: pipeline_kind::Inactive,
-            }
-        };
-
-        Ok(pipeline)
+        Ok(self.into_state(pipeline_kind::Inactive))
     }
 
     /// Unpack the pipeline into raw pointers.
     ///
     /// After calling this method, you have to take care of their lifetime manually.
+    /// The callback registered by [start_with_callback](Pipeline::start_with_callback)
+    /// is leaked.
     pub fn into_raw_parts(
         self,
     ) -> (
@@ -317,8 +441,8 @@ impl ActivePipeline {
         *mut sys::rs2_context,
         *mut sys::rs2_pipeline_profile,
     ) {
-        // take fields without invoking drop()
-        let ptr = self.ptr;
+        // take fields without invoking drop(), and leak the handle to keep the pointer alive
+        let ptr = self.ptr();
         let context = unsafe { self.context.unsafe_clone().into_raw() };
         let pipeline_profile = unsafe { self.state.unsafe_clone().into_raw_parts() };
         mem::forget(self);
@@ -337,7 +461,82 @@ impl ActivePipeline {
         let context = Context::from_raw(context_ptr);
         let state = pipeline_kind::Active::from_raw_parts(profile_ptr);
         Self {
-            ptr: NonNull::new(pipeline_ptr).unwrap(),
+            handle: Arc::new(PipelineHandle {
+                ptr: NonNull::new(pipeline_ptr).unwrap(),
+            }),
+            context,
+            state,
+        }
+    }
+}
+
+/// The stream of frame sets returned by [ActivePipeline::frames](Pipeline::frames).
+#[derive(Debug)]
+pub struct PipelineFrameStream<'a> {
+    shared: Arc<StreamShared>,
+    worker: Option<thread::JoinHandle<()>>,
+    _phantom: PhantomData<&'a mut ActivePipeline>,
+}
+
+#[derive(Debug)]
+struct StreamShared {
+    buffer: Mutex<VecDeque<Result<CompositeFrame>>>,
+    waker: AtomicWaker,
+    stopped: AtomicBool,
+    finished: AtomicBool,
+}
+
+impl<'a> Stream for PipelineFrameStream<'a> {
+    type Item = Result<CompositeFrame>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        // register before checking the buffer to avoid missing wake-ups
+        self.shared.waker.register(cx.waker());
+
+        let finished = self.shared.finished.load(Ordering::SeqCst);
+        match self.shared.buffer.lock().unwrap().pop_front() {
+            Some(result) => Poll::Ready(Some(result)),
+            None if finished => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<'a> Drop for PipelineFrameStream<'a> {
+    fn drop(&mut self) {
+        // the worker leaves within the poll timeout
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl<State> Pipeline<State>
+where
+    State: pipeline_kind::PipelineState,
+{
+    pub(crate) fn ptr(&self) -> NonNull<sys::rs2_pipeline> {
+        self.handle.ptr
+    }
+
+    /// Moves the pipeline handle and context to a pipeline of another state.
+    fn into_state<S>(self, state: S) -> Pipeline<S>
+    where
+        S: pipeline_kind::PipelineState,
+    {
+        let this = mem::ManuallyDrop::new(self);
+        // the fields are read exactly once and the original is never dropped
+        let (handle, context, old_state) = unsafe {
+            (
+                ptr::read(&this.handle),
+                ptr::read(&this.context),
+                ptr::read(&this.state),
+            )
+        };
+        mem::drop(old_state);
+        Pipeline {
+            handle,
             context,
             state,
         }
@@ -348,6 +547,21 @@ impl<State> Drop for Pipeline<State>
 where
     State: pipeline_kind::PipelineState,
 {
+    fn drop(&mut self) {
+        // The pipeline is deleted, and thus stopped, along with the last handle. Stop it
+        // explicitly if workers of leaked streams or futures still hold the handle, so
+        // that the frame callback is not called after it is dropped.
+        if Arc::strong_count(&self.handle) > 1 {
+            unsafe {
+                let mut checker = ErrorChecker::new();
+                sys::rs2_pipeline_stop(self.ptr().as_ptr(), checker.inner_mut_ptr());
+                let _ = checker.check();
+            }
+        }
+    }
+}
+
+impl Drop for PipelineHandle {
     fn drop(&mut self) {
         unsafe {
             sys::rs2_delete_pipeline(self.ptr.as_ptr());
@@ -355,4 +569,18 @@ where
     }
 }
 
+unsafe impl Send for PipelineHandle {}
+
+// SAFETY: The handle is shared with the workers of frame streams and wait futures, which
+// only call `rs2_pipeline_wait_for_frames`. The librealsense pipeline guards start, stop and
+// its frame aggregator by its internal mutex, and the aggregator queue is synchronized, so
+// waits may run concurrently with each other and with the owner of the pipeline.
+unsafe impl Sync for PipelineHandle {}
+
 unsafe impl<State> Send for Pipeline<State> where State: pipeline_kind::PipelineState {}
+
+// SAFETY: Starting, waiting and stopping take &mut self or self. The only shared method is
+// `profile`, which returns the [PipelineProfile] read from the immutable active profile.
+// `Config::resolve` borrows the pipeline, and `rs2_config_resolve` only reads the pipeline
+// device under the pipeline's internal mutex.
+unsafe impl<State> Sync for Pipeline<State> where State: pipeline_kind::PipelineState {}
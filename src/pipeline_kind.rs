@@ -1,6 +1,6 @@
 //! Marker traits and types for [Pipeline](crate::pipeline::Pipeline).
 
-use crate::{common::*, pipeline_profile::PipelineProfile};
+use crate::{callback::CallbackContext, common::*, pipeline_profile::PipelineProfile};
 
 /// Marker trait for pipeline marker types.
 pub trait PipelineState {
@@ -12,18 +12,25 @@ pub trait PipelineState {
 #[derive(Debug)]
 pub struct Active {
     pub profile: PipelineProfile,
+    // the frame callback registered by start_with_callback(), which must outlive the streaming
+    pub(crate) callback: Option<CallbackContext>,
 }
 
 impl Active {
+    /// Unpack the state into the raw profile pointer.
+    ///
+    /// The frame callback, if any, is leaked since it may still be called by librealsense.
     pub fn into_raw_parts(self) -> *mut sys::rs2_pipeline_profile {
         let profile_ptr = unsafe { self.profile.unsafe_clone().into_raw() };
-        mem::forget(self);
+        mem::forget(self.callback);
+        mem::forget(self.profile);
         profile_ptr
     }
 
     pub unsafe fn from_raw_parts(profile_ptr: *mut sys::rs2_pipeline_profile) -> Self {
         Self {
             profile: PipelineProfile::from_raw(profile_ptr),
+            callback: None,
         }
     }
 }
@@ -32,6 +39,7 @@ impl PipelineState for Active {
     unsafe fn unsafe_clone(&self) -> Self {
         Self {
             profile: self.profile.unsafe_clone(),
+            callback: None,
         }
     }
 }
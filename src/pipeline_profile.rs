@@ -67,3 +67,7 @@ impl Drop for PipelineProfile {
 }
 
 unsafe impl Send for PipelineProfile {}
+
+// SAFETY: The librealsense pipeline profile is immutable after the pipeline starts.
+// `device` and `streams` only copy the stored device and stream profiles into new handles.
+unsafe impl Sync for PipelineProfile {}
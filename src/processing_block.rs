@@ -1,15 +1,23 @@
 //! Defines the processing block type.
 
 use crate::{
-    base::StreamProfileData,
+    base::{Intrinsics, Resolution, StreamProfileData},
+    callback::{new_frame_callback, CallbackContext},
     common::*,
-    error::{ErrorChecker, Result},
-    frame::{AnyFrame, DepthFrame, ExtendedFrame, Frame, GenericFrameEx, PointsFrame, VideoFrame},
-    frame_kind::FrameKind,
-    frame_queue::FrameQueue,
-    kind::{ColorScheme, HoleFillingMode, PersistenceControl, Rs2Option, StreamKind},
-    options::ToOptions,
+    error::{Error, ErrorChecker, Result},
+    frame::{
+        AnyFrame, CompositeFrame, DepthFrame, DisparityFrame, ExtendedFrame, Frame, GenericFrameEx,
+        PointsFrame, VideoFrame, VideoFrameEx,
+    },
+    frame_kind::{self, FrameKind},
+    frame_queue::{FrameQueue, FrameStream},
+    frame_source::{processor_callback, FrameProcessor, ProcessorContext, ProcessorHandle},
+    kind::{
+        ColorScheme, ColorizerPreset, HoleFillingMode, PersistenceControl, Rs2Option, StreamKind,
+    },
+    options::{raw_option_ids, OptionHandle, ToOptions},
     processing_block_kind,
+    stream_profile::VideoStreamProfile,
 };
 
 /// The type returned by [ProcessingBlock::<Any>::try_extend](ProcessingBlock::try_extend).
@@ -25,6 +33,8 @@ pub enum ExtendedProcessingBlock {
     TemporalFilter(TemporalFilter),
     HoleFillingFilter(HoleFillingFilter),
     ZeroOrderFilter(ZeroOrderFilter),
+    HdrMerge(HdrMerge),
+    SequenceIdFilter(SequenceIdFilter),
     Other(AnyProcessingBlock),
 }
 
@@ -36,6 +46,8 @@ where
 {
     pub(crate) ptr: NonNull<sys::rs2_processing_block>,
     queue: FrameQueue,
+    processor: Option<ProcessorHandle>,
+    output: Option<CallbackContext>,
     _phantom: PhantomData<Kind>,
 }
 
@@ -48,6 +60,8 @@ pub type SpatialFilter = ProcessingBlock<processing_block_kind::SpatialFilterKin
 pub type TemporalFilter = ProcessingBlock<processing_block_kind::TemporalFilterKind>;
 pub type HoleFillingFilter = ProcessingBlock<processing_block_kind::HoleFillingFilterKind>;
 pub type ZeroOrderFilter = ProcessingBlock<processing_block_kind::ZeroOrderFilterKind>;
+pub type HdrMerge = ProcessingBlock<processing_block_kind::HdrMergeKind>;
+pub type SequenceIdFilter = ProcessingBlock<processing_block_kind::SequenceIdFilterKind>;
 pub type PointCloud = ProcessingBlock<processing_block_kind::PointCloudKind>;
 pub type YuyDecoder = ProcessingBlock<processing_block_kind::YuyDecoderKind>;
 pub type UnitsTransform = ProcessingBlock<processing_block_kind::UnitsTransformKind>;
@@ -57,27 +71,80 @@ pub type Colorizer = ProcessingBlock<processing_block_kind::ColorizerKind>;
 pub type HuffmanDepthDecompress =
     ProcessingBlock<processing_block_kind::HuffmanDepthDecompressKind>;
 pub type RatesPrinter = ProcessingBlock<processing_block_kind::RatesPrinterKind>;
+pub type CustomProcessingBlock = ProcessingBlock<processing_block_kind::CustomKind>;
 pub type AnyProcessingBlock = ProcessingBlock<processing_block_kind::Any>;
 
 impl<Kind> ProcessingBlock<Kind>
 where
     Kind: processing_block_kind::ProcessingBlockKind,
 {
+    /// Processes a frame and waits for the output.
+    ///
+    /// It expects exactly one output per input, and blocks forever if the block emits
+    /// nothing for the input, for example, during the warm-up of temporal filters.
+    /// Use [process_timeout](ProcessingBlock::process_timeout) to bound the wait, or
+    /// [submit](ProcessingBlock::submit) for blocks emitting zero or multiple frames per input.
+    ///
+    /// It returns error before submitting the input if the output is directed to a stream
+    /// by [output_stream](ProcessingBlock::output_stream).
     pub fn process<K>(&mut self, input: Frame<K>) -> Result<AnyFrame>
     where
         K: FrameKind,
     {
-        unsafe {
-            let frame_ptr = input.into_raw();
-            let mut checker = ErrorChecker::new();
-            sys::rs2_process_frame(self.ptr.as_ptr(), frame_ptr, checker.inner_mut_ptr());
-            checker.check()?;
-        }
+        self.check_queue_output()?;
+        self.submit(input)?;
         let output = self.queue.wait(None)?;
         Ok(output)
     }
 
+    /// Processes a frame and waits for the output at most `timeout`.
+    ///
+    /// It returns [Error::Timeout] if the block emits nothing in time. The late output
+    /// is discarded by the next call. Like [process](ProcessingBlock::process), it returns
+    /// error while the output is directed to a stream.
+    pub fn process_timeout<K>(&mut self, input: Frame<K>, timeout: Duration) -> Result<AnyFrame>
+    where
+        K: FrameKind,
+    {
+        self.submit_discarding_outputs(input)?;
+        let output = self.queue.wait(Some(timeout))?;
+        Ok(output)
+    }
+
+    /// Processes a frame and waits for the output asynchronously.
+    ///
+    /// It is analogous to [process](ProcessingBlock::process).
     pub async fn process_async<K>(&mut self, input: Frame<K>) -> Result<AnyFrame>
+    where
+        K: FrameKind,
+    {
+        self.check_queue_output()?;
+        self.submit(input)?;
+        let output = self.queue.wait_async(None).await?;
+        Ok(output)
+    }
+
+    /// Processes a frame and waits for the output asynchronously at most `timeout`.
+    ///
+    /// It is analogous to [process_timeout](ProcessingBlock::process_timeout).
+    pub async fn process_timeout_async<K>(
+        &mut self,
+        input: Frame<K>,
+        timeout: Duration,
+    ) -> Result<AnyFrame>
+    where
+        K: FrameKind,
+    {
+        self.submit_discarding_outputs(input)?;
+        let output = self.queue.wait_async(Some(timeout)).await?;
+        Ok(output)
+    }
+
+    /// Pushes a frame to the block without waiting for the output.
+    ///
+    /// The outputs are delivered to the [output queue](ProcessingBlock::output_queue),
+    /// or to the stream returned by [output_stream](ProcessingBlock::output_stream).
+    pub fn submit<K>(&mut self, input: Frame<K>) -> Result<()>
     where
         K: FrameKind,
     {
@@ -87,21 +154,132 @@ where
             sys::rs2_process_frame(self.ptr.as_ptr(), frame_ptr, checker.inner_mut_ptr());
             checker.check()?;
         }
-        let output = self.queue.wait_async(None).await?;
-        Ok(output)
+        Ok(())
+    }
+
+    /// Gets the queue receiving the output frames.
+    pub fn output_queue(&mut self) -> &mut FrameQueue {
+        &mut self.queue
+    }
+
+    /// Directs the output to a new queue with given capacity.
+    ///
+    /// The queue capacity is 1 by default. Frames arriving at a full queue are dropped.
+    pub fn set_output_queue_capacity(&mut self, capacity: usize) -> Result<()> {
+        let queue = FrameQueue::with_capacity(capacity)?;
+        unsafe {
+            let mut checker = ErrorChecker::new();
+            sys::rs2_start_processing_queue(
+                self.ptr.as_ptr(),
+                queue.ptr().as_ptr(),
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+        }
+        self.queue = queue;
+        self.output = None;
+        Ok(())
+    }
+
+    /// Directs the output to a stream buffering at most `capacity` frames.
+    ///
+    /// Frames arriving at a full buffer are dropped. Afterwards [process](ProcessingBlock::process)
+    /// returns error until the output is directed back to the queue by
+    /// [set_output_queue_capacity](ProcessingBlock::set_output_queue_capacity).
+    /// The stream ends when the block, or the block it is converted into, is dropped.
+    pub fn output_stream(&mut self, capacity: usize) -> Result<FrameStream> {
+        let (mut tx, rx) = futures::channel::mpsc::channel(capacity);
+        let (callback, context) = new_frame_callback(move |frame| {
+            let _ = tx.try_send(frame);
+        });
+
+        unsafe {
+            let mut checker = ErrorChecker::new();
+            sys::rs2_start_processing_fptr(
+                self.ptr.as_ptr(),
+                callback,
+                context.as_ptr(),
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+        }
+        self.output = Some(context);
+        Ok(FrameStream::new(rx))
+    }
+
+    /// Submits a frame after discarding the outputs left by timed out calls.
+    fn submit_discarding_outputs<K>(&mut self, input: Frame<K>) -> Result<()>
+    where
+        K: FrameKind,
+    {
+        self.check_queue_output()?;
+        while self.queue.try_wait()?.is_some() {}
+        self.submit(input)
+    }
+
+    fn check_queue_output(&self) -> Result<()> {
+        if self.output.is_some() {
+            return Err(Error::InvalidData(
+                "the output is directed to a stream instead of the queue".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Gets the value of an option.
+    pub fn option_value(&self, option: Rs2Option) -> Result<f32> {
+        self.option_handle(option)?.value()
+    }
+
+    /// Sets the value of an option, which must be within the [range](OptionHandle::range).
+    pub fn set_option_value(&self, option: Rs2Option, value: f32) -> Result<()> {
+        self.option_handle(option)?.set_value_checked(value)
+    }
+
+    fn option_handle(&self, option: Rs2Option) -> Result<OptionHandle> {
+        self.to_options()?
+            .remove(&option)
+            .ok_or_else(|| Error::InvalidData(format!("option {:?} is not supported", option)))
+    }
+
+    /// Erases the processing block kind.
+    pub fn into_any(self) -> AnyProcessingBlock {
+        self.into_kind()
     }
 
+    /// Changes the processing block kind, carrying the processor and the output stream over.
+    fn into_kind<K>(mut self) -> ProcessingBlock<K>
+    where
+        K: processing_block_kind::ProcessingBlockKind,
+    {
+        let processor = self.processor.take();
+        let output = self.output.take();
+        let (ptr, queue) = self.into_raw_parts();
+        let mut block = unsafe { ProcessingBlock::from_raw_parts(ptr, queue) };
+        block.processor = processor;
+        block.output = output;
+        block
+    }
+
+    /// Destructs and returns the raw pointer and the output queue.
+    ///
+    /// The processor of a [CustomProcessingBlock] and the sender of
+    /// [output_stream](ProcessingBlock::output_stream) are leaked since they may be
+    /// still used by the returned block. [into_any](ProcessingBlock::into_any) and
+    /// [try_extend_to](AnyProcessingBlock::try_extend_to) keep them instead.
     pub fn into_raw_parts(self) -> (*mut sys::rs2_processing_block, FrameQueue) {
-        let ptr = self.ptr;
-        let queue = unsafe { self.queue.unsafe_clone() };
-        mem::forget(self);
-        (ptr.as_ptr(), queue)
+        let this = mem::ManuallyDrop::new(self);
+        // move the queue out, and leave other fields undropped
+        let queue = unsafe { ptr::read(&this.queue) };
+        (this.ptr.as_ptr(), queue)
     }
 
     pub unsafe fn from_raw_parts(ptr: *mut sys::rs2_processing_block, queue: FrameQueue) -> Self {
         Self {
             ptr: NonNull::new(ptr).unwrap(),
             queue,
+            processor: None,
+            output: None,
             _phantom: PhantomData,
         }
     }
@@ -117,7 +295,7 @@ where
             let mut checker = ErrorChecker::new();
             sys::rs2_start_processing_queue(
                 ptr.as_ptr(),
-                queue.ptr.as_ptr(),
+                queue.ptr().as_ptr(),
                 checker.inner_mut_ptr(),
             );
             checker.check()?;
@@ -126,6 +304,8 @@ where
         let block = Self {
             ptr,
             queue,
+            processor: None,
+            output: None,
             _phantom: PhantomData,
         };
         Ok(block)
@@ -157,15 +337,10 @@ impl AnyProcessingBlock {
     where
         Kind: processing_block_kind::ExtendableProcessingBlockKind,
     {
-        unsafe {
-            let is_extendable = self.is_extendable_to::<Kind>()?;
-            if is_extendable {
-                let (ptr, queue) = self.into_raw_parts();
-                let block = ProcessingBlock::from_raw_parts(ptr, queue);
-                Ok(Ok(block))
-            } else {
-                Ok(Err(self))
-            }
+        if self.is_extendable_to::<Kind>()? {
+            Ok(Ok(self.into_kind()))
+        } else {
+            Ok(Err(self))
         }
     }
 
@@ -214,6 +389,17 @@ impl AnyProcessingBlock {
                 Err(frame) => frame,
             };
 
+        let frame_any = match frame_any.try_extend_to::<processing_block_kind::HdrMergeKind>()? {
+            Ok(frame) => return Ok(ExtendedProcessingBlock::HdrMerge(frame)),
+            Err(frame) => frame,
+        };
+
+        let frame_any =
+            match frame_any.try_extend_to::<processing_block_kind::SequenceIdFilterKind>()? {
+                Ok(frame) => return Ok(ExtendedProcessingBlock::SequenceIdFilter(frame)),
+                Err(frame) => frame,
+            };
+
         Ok(ExtendedProcessingBlock::Other(frame_any))
     }
 }
@@ -233,26 +419,35 @@ impl ThresholdFilter {
         min_dist: impl Into<Option<f32>>,
         max_dist: impl Into<Option<f32>>,
     ) -> Result<Self> {
-        let min_dist = min_dist.into();
-        let max_dist = max_dist.into();
-
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_threshold(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
-
-        let options = processing_block.to_options()?;
-        if let Some(dist) = min_dist {
-            options[&Rs2Option::MinDistance].set_value(dist)?;
+        let processing_block = Self::create()?;
+        if let Some(dist) = min_dist.into() {
+            processing_block.set_min_distance(dist)?;
         }
-        if let Some(dist) = max_dist {
-            options[&Rs2Option::MaxDistance].set_value(dist)?;
+        if let Some(dist) = max_dist.into() {
+            processing_block.set_max_distance(dist)?;
         }
-
         Ok(processing_block)
     }
+
+    /// Gets the minimum distance in meters.
+    pub fn min_distance(&self) -> Result<f32> {
+        self.option_value(Rs2Option::MinDistance)
+    }
+
+    /// Sets the minimum distance in meters.
+    pub fn set_min_distance(&self, dist: f32) -> Result<()> {
+        self.set_option_value(Rs2Option::MinDistance, dist)
+    }
+
+    /// Gets the maximum distance in meters.
+    pub fn max_distance(&self) -> Result<f32> {
+        self.option_value(Rs2Option::MaxDistance)
+    }
+
+    /// Sets the maximum distance in meters.
+    pub fn set_max_distance(&self, dist: f32) -> Result<()> {
+        self.set_option_value(Rs2Option::MaxDistance, dist)
+    }
 }
 
 impl SpatialFilter {
@@ -269,23 +464,55 @@ impl SpatialFilter {
     pub fn with_options(
         smooth_alpha: f32,
         smooth_delta: f32,
-        magnitude: f32,
-        hole_fill: f32,
+        magnitude: usize,
+        hole_fill: usize,
     ) -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_temporal_filter_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
+        let processing_block = Self::create()?;
+        processing_block.set_smooth_alpha(smooth_alpha)?;
+        processing_block.set_smooth_delta(smooth_delta)?;
+        processing_block.set_magnitude(magnitude)?;
+        processing_block.set_holes_fill(hole_fill)?;
+        Ok(processing_block)
+    }
 
-        let options = processing_block.to_options()?;
-        options[&Rs2Option::FilterSmoothAlpha].set_value(smooth_alpha)?;
-        options[&Rs2Option::FilterSmoothDelta].set_value(smooth_delta)?;
-        options[&Rs2Option::FilterMagnitude].set_value(magnitude)?;
-        options[&Rs2Option::HolesFill].set_value(hole_fill)?;
+    /// Gets the alpha factor of exponential moving average.
+    pub fn smooth_alpha(&self) -> Result<f32> {
+        self.option_value(Rs2Option::FilterSmoothAlpha)
+    }
 
-        Ok(processing_block)
+    /// Sets the alpha factor of exponential moving average.
+    pub fn set_smooth_alpha(&self, alpha: f32) -> Result<()> {
+        self.set_option_value(Rs2Option::FilterSmoothAlpha, alpha)
+    }
+
+    /// Gets the step size boundary to preserve edges.
+    pub fn smooth_delta(&self) -> Result<f32> {
+        self.option_value(Rs2Option::FilterSmoothDelta)
+    }
+
+    /// Sets the step size boundary to preserve edges.
+    pub fn set_smooth_delta(&self, delta: f32) -> Result<()> {
+        self.set_option_value(Rs2Option::FilterSmoothDelta, delta)
+    }
+
+    /// Gets the number of filter iterations.
+    pub fn magnitude(&self) -> Result<usize> {
+        Ok(self.option_value(Rs2Option::FilterMagnitude)?.round() as usize)
+    }
+
+    /// Sets the number of filter iterations.
+    pub fn set_magnitude(&self, magnitude: usize) -> Result<()> {
+        self.set_option_value(Rs2Option::FilterMagnitude, magnitude as f32)
+    }
+
+    /// Gets the hole filling radius, where 0 disables hole filling.
+    pub fn holes_fill(&self) -> Result<usize> {
+        Ok(self.option_value(Rs2Option::HolesFill)?.round() as usize)
+    }
+
+    /// Sets the hole filling radius, where 0 disables hole filling.
+    pub fn set_holes_fill(&self, holes_fill: usize) -> Result<()> {
+        self.set_option_value(Rs2Option::HolesFill, holes_fill as f32)
     }
 }
 
@@ -305,19 +532,44 @@ impl TemporalFilter {
         smooth_delta: f32,
         persistence_control: PersistenceControl,
     ) -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_temporal_filter_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
+        let processing_block = Self::create()?;
+        processing_block.set_persistence(persistence_control)?;
+        processing_block.set_smooth_alpha(smooth_alpha)?;
+        processing_block.set_smooth_delta(smooth_delta)?;
+        Ok(processing_block)
+    }
 
-        let options = processing_block.to_options()?;
-        options[&Rs2Option::HolesFill].set_value(persistence_control as usize as f32)?;
-        options[&Rs2Option::FilterSmoothAlpha].set_value(smooth_alpha)?;
-        options[&Rs2Option::FilterSmoothDelta].set_value(smooth_delta)?;
+    /// Gets the alpha factor of exponential moving average.
+    pub fn smooth_alpha(&self) -> Result<f32> {
+        self.option_value(Rs2Option::FilterSmoothAlpha)
+    }
 
-        Ok(processing_block)
+    /// Sets the alpha factor of exponential moving average.
+    pub fn set_smooth_alpha(&self, alpha: f32) -> Result<()> {
+        self.set_option_value(Rs2Option::FilterSmoothAlpha, alpha)
+    }
+
+    /// Gets the step size boundary to preserve edges.
+    pub fn smooth_delta(&self) -> Result<f32> {
+        self.option_value(Rs2Option::FilterSmoothDelta)
+    }
+
+    /// Sets the step size boundary to preserve edges.
+    pub fn set_smooth_delta(&self, delta: f32) -> Result<()> {
+        self.set_option_value(Rs2Option::FilterSmoothDelta, delta)
+    }
+
+    /// Gets the persistence mode.
+    pub fn persistence(&self) -> Result<PersistenceControl> {
+        let value = self.option_value(Rs2Option::HolesFill)?;
+        PersistenceControl::from_usize(value as usize).ok_or_else(|| {
+            Error::InvalidData(format!("unknown persistence control value {}", value))
+        })
+    }
+
+    /// Sets the persistence mode.
+    pub fn set_persistence(&self, persistence_control: PersistenceControl) -> Result<()> {
+        self.set_option_value(Rs2Option::HolesFill, persistence_control as usize as f32)
     }
 }
 
@@ -332,18 +584,20 @@ impl DecimationFilter {
         Ok(processing_block)
     }
 
-    pub fn with_options(magnitude: f32) -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_decimation_filter_block(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
+    pub fn with_options(magnitude: usize) -> Result<Self> {
+        let processing_block = Self::create()?;
+        processing_block.set_magnitude(magnitude)?;
+        Ok(processing_block)
+    }
 
-        let options = processing_block.to_options()?;
-        options[&Rs2Option::FilterMagnitude].set_value(magnitude)?;
+    /// Gets the decimation scale factor.
+    pub fn magnitude(&self) -> Result<usize> {
+        Ok(self.option_value(Rs2Option::FilterMagnitude)?.round() as usize)
+    }
 
-        Ok(processing_block)
+    /// Sets the decimation scale factor.
+    pub fn set_magnitude(&self, magnitude: usize) -> Result<()> {
+        self.set_option_value(Rs2Option::FilterMagnitude, magnitude as f32)
     }
 }
 
@@ -360,11 +614,20 @@ impl HoleFillingFilter {
 
     pub fn with_options(mode: HoleFillingMode) -> Result<Self> {
         let processing_block = Self::create()?;
+        processing_block.set_mode(mode)?;
+        Ok(processing_block)
+    }
 
-        let options = processing_block.to_options()?;
-        options[&Rs2Option::HolesFill].set_value(mode as usize as f32)?;
+    /// Gets the hole filling mode.
+    pub fn mode(&self) -> Result<HoleFillingMode> {
+        let value = self.option_value(Rs2Option::HolesFill)?;
+        HoleFillingMode::from_usize(value as usize)
+            .ok_or_else(|| Error::InvalidData(format!("unknown hole filling mode {}", value)))
+    }
 
-        Ok(processing_block)
+    /// Sets the hole filling mode.
+    pub fn set_mode(&self, mode: HoleFillingMode) -> Result<()> {
+        self.set_option_value(Rs2Option::HolesFill, mode as usize as f32)
     }
 }
 
@@ -385,6 +648,233 @@ impl DisparityFilter {
         };
         Ok(processing_block)
     }
+
+    /// Converts a depth frame to a disparity frame.
+    ///
+    /// The filter must be created with `transform_to_disparity` set to true.
+    pub fn to_disparity(&mut self, depth_frame: DepthFrame) -> Result<DisparityFrame> {
+        let frame_any = self.process(depth_frame)?;
+        let disparity_frame: DisparityFrame = frame_any.try_extend_to()?.map_err(|_| {
+            Error::InvalidData("disparity filter output is not a disparity frame".into())
+        })?;
+        Ok(disparity_frame)
+    }
+
+    /// Converts a disparity frame back to a depth frame.
+    ///
+    /// The filter must be created with `transform_to_disparity` set to false.
+    pub fn to_depth(&mut self, disparity_frame: DisparityFrame) -> Result<DepthFrame> {
+        let frame_any = self.process(disparity_frame)?;
+        // disparity frames are also depth frames
+        if frame_any.is_extendable_to::<frame_kind::Disparity>()? {
+            return Err(Error::InvalidData(
+                "disparity filter output is not a depth frame".into(),
+            ));
+        }
+        let depth_frame: DepthFrame = frame_any.try_extend_to()?.map_err(|_| {
+            Error::InvalidData("disparity filter output is not a depth frame".into())
+        })?;
+        Ok(depth_frame)
+    }
+}
+
+/// The descriptions of the options registered by the zero order filter in librealsense 2.39.
+///
+/// The options have no `rs2_option` counterpart. librealsense numbers them after
+/// [Rs2Option::Count], which shifts whenever `rs2_option` grows, so they are looked up by
+/// description instead.
+mod zero_order_option {
+    pub const IR_THRESHOLD: &str = "IR threshold";
+    pub const RMS_THRESHOLD: &str = "RMS threshold";
+    pub const NEIGHBORS_THRESHOLD: &str = "Neighbors threshold";
+    pub const BASELINE: &str = "Baseline";
+    pub const PATCH_SIZE: &str = "Patch size";
+    pub const MAX_VALUE: &str = "ZO max value";
+    pub const IR_MIN_VALUE: &str = "Minimum IR value";
+    pub const THRESHOLD_OFFSET: &str = "Threshold offset";
+    pub const THRESHOLD_SCALE: &str = "Threshold scale";
+}
+
+impl ZeroOrderFilter {
+    pub fn create() -> Result<Self> {
+        let processing_block = unsafe {
+            let mut checker = ErrorChecker::new();
+            let ptr = sys::rs2_create_zero_order_invalidation_block(checker.inner_mut_ptr());
+            checker.check()?;
+            Self::new_from_raw(NonNull::new(ptr).unwrap())?
+        };
+        Ok(processing_block)
+    }
+
+    /// Gets the IR intensity threshold of zero order pixels.
+    pub fn ir_threshold(&self) -> Result<f32> {
+        let option = self.zero_order_option(zero_order_option::IR_THRESHOLD)?;
+        option.value()
+    }
+
+    /// Sets the IR intensity threshold of zero order pixels.
+    pub fn set_ir_threshold(&self, value: f32) -> Result<()> {
+        let option = self.zero_order_option(zero_order_option::IR_THRESHOLD)?;
+        option.set_value_checked(value)
+    }
+
+    /// Gets the RMS threshold of zero order pixels.
+    pub fn rms_threshold(&self) -> Result<f32> {
+        let option = self.zero_order_option(zero_order_option::RMS_THRESHOLD)?;
+        option.value()
+    }
+
+    /// Sets the RMS threshold of zero order pixels.
+    pub fn set_rms_threshold(&self, value: f32) -> Result<()> {
+        let option = self.zero_order_option(zero_order_option::RMS_THRESHOLD)?;
+        option.set_value_checked(value)
+    }
+
+    /// Gets the minimum number of valid neighbors to keep a pixel.
+    pub fn neighbors_threshold(&self) -> Result<usize> {
+        let option = self.zero_order_option(zero_order_option::NEIGHBORS_THRESHOLD)?;
+        Ok(option.value()?.round() as usize)
+    }
+
+    /// Sets the minimum number of valid neighbors to keep a pixel.
+    pub fn set_neighbors_threshold(&self, value: usize) -> Result<()> {
+        let option = self.zero_order_option(zero_order_option::NEIGHBORS_THRESHOLD)?;
+        option.set_value_checked(value as f32)
+    }
+
+    /// Gets the baseline between the projector and the depth imager.
+    pub fn baseline(&self) -> Result<f32> {
+        let option = self.zero_order_option(zero_order_option::BASELINE)?;
+        option.value()
+    }
+
+    /// Sets the baseline between the projector and the depth imager.
+    pub fn set_baseline(&self, value: f32) -> Result<()> {
+        let option = self.zero_order_option(zero_order_option::BASELINE)?;
+        option.set_value_checked(value)
+    }
+
+    /// Gets the size of the patch around the zero order point.
+    pub fn patch_size(&self) -> Result<usize> {
+        let option = self.zero_order_option(zero_order_option::PATCH_SIZE)?;
+        Ok(option.value()?.round() as usize)
+    }
+
+    /// Sets the size of the patch around the zero order point.
+    pub fn set_patch_size(&self, value: usize) -> Result<()> {
+        let option = self.zero_order_option(zero_order_option::PATCH_SIZE)?;
+        option.set_value_checked(value as f32)
+    }
+
+    /// Gets the maximum depth value of zero order pixels.
+    pub fn max_value(&self) -> Result<f32> {
+        let option = self.zero_order_option(zero_order_option::MAX_VALUE)?;
+        option.value()
+    }
+
+    /// Sets the maximum depth value of zero order pixels.
+    pub fn set_max_value(&self, value: f32) -> Result<()> {
+        let option = self.zero_order_option(zero_order_option::MAX_VALUE)?;
+        option.set_value_checked(value)
+    }
+
+    /// Gets the minimum IR intensity of valid pixels.
+    pub fn ir_min_value(&self) -> Result<f32> {
+        let option = self.zero_order_option(zero_order_option::IR_MIN_VALUE)?;
+        option.value()
+    }
+
+    /// Sets the minimum IR intensity of valid pixels.
+    pub fn set_ir_min_value(&self, value: f32) -> Result<()> {
+        let option = self.zero_order_option(zero_order_option::IR_MIN_VALUE)?;
+        option.set_value_checked(value)
+    }
+
+    /// Gets the offset of the zero order threshold.
+    pub fn threshold_offset(&self) -> Result<f32> {
+        let option = self.zero_order_option(zero_order_option::THRESHOLD_OFFSET)?;
+        option.value()
+    }
+
+    /// Sets the offset of the zero order threshold.
+    pub fn set_threshold_offset(&self, value: f32) -> Result<()> {
+        let option = self.zero_order_option(zero_order_option::THRESHOLD_OFFSET)?;
+        option.set_value_checked(value)
+    }
+
+    /// Gets the scale of the zero order threshold.
+    pub fn threshold_scale(&self) -> Result<f32> {
+        let option = self.zero_order_option(zero_order_option::THRESHOLD_SCALE)?;
+        option.value()
+    }
+
+    /// Sets the scale of the zero order threshold.
+    pub fn set_threshold_scale(&self, value: f32) -> Result<()> {
+        let option = self.zero_order_option(zero_order_option::THRESHOLD_SCALE)?;
+        option.set_value_checked(value)
+    }
+
+    /// Finds an option registered by the filter by its description.
+    fn zero_order_option(&self, description: &str) -> Result<OptionHandle> {
+        let options_ptr = self.options_ptr();
+        for option in raw_option_ids(options_ptr)? {
+            if option < sys::rs2_option_RS2_OPTION_COUNT {
+                continue;
+            }
+            let handle = OptionHandle::from_raw_parts(options_ptr, option);
+            if handle.option_description()? == description {
+                return Ok(handle);
+            }
+        }
+        Err(Error::InvalidData(format!(
+            "option {:?} is not supported",
+            description
+        )))
+    }
+}
+
+impl HdrMerge {
+    /// Creates a block merging depth frames of alternating exposures into a single frame.
+    ///
+    /// The input is the composite frame from a depth sensor with [HdrEnabled](Rs2Option::HdrEnabled) set.
+    pub fn create() -> Result<Self> {
+        let processing_block = unsafe {
+            let mut checker = ErrorChecker::new();
+            let ptr = sys::rs2_create_hdr_merge_processing_block(checker.inner_mut_ptr());
+            checker.check()?;
+            Self::new_from_raw(NonNull::new(ptr).unwrap())?
+        };
+        Ok(processing_block)
+    }
+}
+
+impl SequenceIdFilter {
+    pub fn create() -> Result<Self> {
+        let processing_block = unsafe {
+            let mut checker = ErrorChecker::new();
+            let ptr = sys::rs2_create_sequence_id_filter(checker.inner_mut_ptr());
+            checker.check()?;
+            Self::new_from_raw(NonNull::new(ptr).unwrap())?
+        };
+        Ok(processing_block)
+    }
+
+    /// Creates a block that only passes frames with given HDR sequence ID.
+    pub fn with_options(sequence_id: usize) -> Result<Self> {
+        let processing_block = Self::create()?;
+        processing_block.set_sequence_id(sequence_id)?;
+        Ok(processing_block)
+    }
+
+    /// Gets the HDR sequence ID of frames to pass.
+    pub fn sequence_id(&self) -> Result<usize> {
+        Ok(self.option_value(Rs2Option::SequenceId)?.round() as usize)
+    }
+
+    /// Sets the HDR sequence ID of frames to pass.
+    pub fn set_sequence_id(&self, sequence_id: usize) -> Result<()> {
+        self.set_option_value(Rs2Option::SequenceId, sequence_id as f32)
+    }
 }
 
 impl PointCloud {
@@ -487,6 +977,15 @@ impl UnitsTransform {
         };
         Ok(processing_block)
     }
+
+    /// Converts a depth frame to a frame of [Distance](Format::Distance) format in meters.
+    pub fn to_meters(&mut self, depth_frame: DepthFrame) -> Result<VideoFrame> {
+        let frame_any = self.process(depth_frame)?;
+        let distance_frame: VideoFrame = frame_any.try_extend_to()?.map_err(|_| {
+            Error::InvalidData("units transform output is not a video frame".into())
+        })?;
+        Ok(distance_frame)
+    }
 }
 
 impl Syncer {
@@ -511,6 +1010,107 @@ impl Align {
         };
         Ok(processing_block)
     }
+
+    /// Aligns the depth and color frames in a frame set.
+    pub fn align(&mut self, frames: CompositeFrame) -> Result<AlignedFrames> {
+        let input_ids = profile_ids(&frames)?;
+        let output = self.process(frames)?;
+        AlignedFrames::from_frame(output, &input_ids)
+    }
+
+    /// Aligns the depth and color frames in a frame set asynchronously.
+    ///
+    /// It is analogous to [align](Align::align).
+    pub async fn align_async(&mut self, frames: CompositeFrame) -> Result<AlignedFrames> {
+        let input_ids = profile_ids(&frames)?;
+        let output = self.process_async(frames).await?;
+        AlignedFrames::from_frame(output, &input_ids)
+    }
+}
+
+/// Collects the unique IDs of stream profiles of frames in a frame set.
+fn profile_ids(frames: &CompositeFrame) -> Result<Vec<i32>> {
+    frames
+        .try_iter()?
+        .map(|frame| Ok(frame?.stream_profile()?.get_data()?.unique_id))
+        .collect()
+}
+
+/// The depth and color frames in the same viewport, returned by [Align::align].
+#[derive(Debug)]
+pub struct AlignedFrames {
+    pub depth: DepthFrame,
+    pub color: VideoFrame,
+    aligned_stream: StreamKind,
+}
+
+impl AlignedFrames {
+    /// Gets the common resolution of depth and color frames.
+    pub fn resolution(&self) -> Result<Resolution> {
+        self.depth.resolution()
+    }
+
+    /// Gets the stream of the frame that is reprojected to the viewport of the other.
+    pub fn aligned_stream(&self) -> StreamKind {
+        self.aligned_stream
+    }
+
+    /// Gets the common intrinsic parameters of depth and color frames.
+    ///
+    /// They are read from the stream profile of the [aligned](AlignedFrames::aligned_stream) frame.
+    pub fn intrinsics(&self) -> Result<Intrinsics> {
+        let profile = match self.aligned_stream {
+            StreamKind::Color => self.color.stream_profile()?,
+            _ => self.depth.stream_profile()?,
+        };
+        let profile: VideoStreamProfile = profile
+            .try_extend_to()?
+            .map_err(|_| Error::InvalidData("aligned frame has no video stream profile".into()))?;
+        profile.intrinsics()
+    }
+
+    /// Splits the align output, where the aligned frame is the one with a new stream profile.
+    fn from_frame(frame: AnyFrame, input_ids: &[i32]) -> Result<Self> {
+        let frames: CompositeFrame = frame
+            .try_extend_to()?
+            .map_err(|_| Error::InvalidData("align output is not a composite frame".into()))?;
+
+        let mut depth = None;
+        let mut color = None;
+        for result in frames.try_into_iter()? {
+            let frame = result?;
+            let frame = match frame.try_extend_to::<frame_kind::Depth>()? {
+                Ok(frame) => {
+                    depth.get_or_insert(frame);
+                    continue;
+                }
+                Err(frame) => frame,
+            };
+            if frame.stream_profile()?.get_data()?.stream == StreamKind::Color {
+                if let Ok(frame) = frame.try_extend_to::<frame_kind::Video>()? {
+                    color.get_or_insert(frame);
+                }
+            }
+        }
+
+        match (depth, color) {
+            (Some(depth), Some(color)) => {
+                let color_id = color.stream_profile()?.get_data()?.unique_id;
+                let aligned_stream = if input_ids.contains(&color_id) {
+                    StreamKind::Depth
+                } else {
+                    StreamKind::Color
+                };
+                Ok(Self {
+                    depth,
+                    color,
+                    aligned_stream,
+                })
+            }
+            (None, _) => Err(Error::InvalidData("no depth frame in frame set".into())),
+            (_, None) => Err(Error::InvalidData("no color frame in frame set".into())),
+        }
+    }
 }
 
 impl Colorizer {
@@ -525,17 +1125,69 @@ impl Colorizer {
     }
 
     pub fn with_options(color_scheme: ColorScheme) -> Result<Self> {
-        let processing_block = unsafe {
-            let mut checker = ErrorChecker::new();
-            let ptr = sys::rs2_create_colorizer(checker.inner_mut_ptr());
-            checker.check()?;
-            Self::new_from_raw(NonNull::new(ptr).unwrap())?
-        };
+        let processing_block = Self::create()?;
+        processing_block.set_color_scheme(color_scheme)?;
+        Ok(processing_block)
+    }
+
+    /// Gets the color scheme.
+    pub fn color_scheme(&self) -> Result<ColorScheme> {
+        let value = self.option_value(Rs2Option::ColorScheme)?;
+        ColorScheme::from_usize(value as usize)
+            .ok_or_else(|| Error::InvalidData(format!("unknown color scheme {}", value)))
+    }
 
-        let options = processing_block.to_options()?;
-        options[&Rs2Option::ColorScheme].set_value(color_scheme as usize as f32)?;
+    /// Sets the color scheme.
+    pub fn set_color_scheme(&self, color_scheme: ColorScheme) -> Result<()> {
+        self.set_option_value(Rs2Option::ColorScheme, color_scheme as usize as f32)
+    }
 
-        Ok(processing_block)
+    /// Checks if histogram equalization is enabled.
+    pub fn histogram_equalization(&self) -> Result<bool> {
+        Ok(self.option_value(Rs2Option::HistogramEqualizationEnabled)? != 0.0)
+    }
+
+    /// Enables or disables histogram equalization.
+    ///
+    /// The [min_distance](Colorizer::min_distance) and [max_distance](Colorizer::max_distance)
+    /// only take effect when histogram equalization is disabled.
+    pub fn set_histogram_equalization(&self, enabled: bool) -> Result<()> {
+        self.set_option_value(
+            Rs2Option::HistogramEqualizationEnabled,
+            enabled as usize as f32,
+        )
+    }
+
+    /// Gets the distance in meters mapped to the first color.
+    pub fn min_distance(&self) -> Result<f32> {
+        self.option_value(Rs2Option::MinDistance)
+    }
+
+    /// Sets the distance in meters mapped to the first color.
+    pub fn set_min_distance(&self, dist: f32) -> Result<()> {
+        self.set_option_value(Rs2Option::MinDistance, dist)
+    }
+
+    /// Gets the distance in meters mapped to the last color.
+    pub fn max_distance(&self) -> Result<f32> {
+        self.option_value(Rs2Option::MaxDistance)
+    }
+
+    /// Sets the distance in meters mapped to the last color.
+    pub fn set_max_distance(&self, dist: f32) -> Result<()> {
+        self.set_option_value(Rs2Option::MaxDistance, dist)
+    }
+
+    /// Gets the visual preset.
+    pub fn visual_preset(&self) -> Result<ColorizerPreset> {
+        let value = self.option_value(Rs2Option::VisualPreset)?;
+        ColorizerPreset::from_usize(value as usize)
+            .ok_or_else(|| Error::InvalidData(format!("unknown colorizer preset {}", value)))
+    }
+
+    /// Sets the visual preset, which overrides the color scheme and distance range.
+    pub fn set_visual_preset(&self, preset: ColorizerPreset) -> Result<()> {
+        self.set_option_value(Rs2Option::VisualPreset, preset as usize as f32)
     }
 
     pub fn colorize(&mut self, depth_frame: DepthFrame) -> Result<VideoFrame> {
@@ -569,6 +1221,96 @@ impl RatesPrinter {
     }
 }
 
+impl CustomProcessingBlock {
+    /// Creates a processing block that runs the user-defined processor.
+    ///
+    /// The output frames are sent by [FrameSource::frame_ready](crate::frame_source::FrameSource::frame_ready) or
+    /// [FrameSource::pass_through](crate::frame_source::FrameSource::pass_through) within the processor.
+    pub fn create<P>(processor: P) -> Result<Self>
+    where
+        P: FrameProcessor,
+    {
+        let error = Arc::new(Mutex::new(None));
+        let handle = CallbackContext::new(Box::new(ProcessorContext {
+            processor,
+            block_ptr: None,
+            error: error.clone(),
+        }));
+
+        let ptr = unsafe {
+            let mut checker = ErrorChecker::new();
+            let ptr = sys::rs2_create_processing_block_fptr(
+                Some(processor_callback::<P>),
+                handle.as_ptr(),
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+            NonNull::new(ptr).unwrap()
+        };
+
+        // the callback is not called until processing starts
+        unsafe {
+            (*handle.as_ptr().cast::<ProcessorContext<P>>()).block_ptr = Some(ptr);
+        }
+
+        let mut processing_block = match unsafe { Self::new_from_raw(ptr) } {
+            Ok(processing_block) => processing_block,
+            Err(err) => {
+                // the block must be deleted before the processor it calls
+                unsafe {
+                    sys::rs2_delete_processing_block(ptr.as_ptr());
+                }
+                return Err(err);
+            }
+        };
+        processing_block.processor = Some(ProcessorHandle {
+            _context: handle,
+            error,
+        });
+        Ok(processing_block)
+    }
+
+    /// Takes the last error returned by the processor since the previous call.
+    pub fn take_processor_error(&self) -> Option<Error> {
+        let handle = self.processor.as_ref()?;
+        let mut error = handle.error.lock().ok()?;
+        error.take()
+    }
+
+    /// Registers a custom option, which is accessible by [ToOptions::to_options]
+    /// and [FrameSource::option_value](crate::frame_source::FrameSource::option_value).
+    pub fn register_option(
+        &mut self,
+        option: Rs2Option,
+        min: f32,
+        max: f32,
+        step: f32,
+        default: f32,
+    ) -> Result<()> {
+        let success = unsafe {
+            let mut checker = ErrorChecker::new();
+            let val = sys::rs2_processing_block_register_simple_option(
+                self.ptr.as_ptr(),
+                option as sys::rs2_option,
+                min,
+                max,
+                step,
+                default,
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+            val != 0
+        };
+        if !success {
+            return Err(Error::InvalidData(format!(
+                "option {:?} is already registered",
+                option
+            )));
+        }
+        Ok(())
+    }
+}
+
 impl<Kind> ToOptions for ProcessingBlock<Kind>
 where
     Kind: processing_block_kind::ProcessingBlockKind,
@@ -593,3 +1335,75 @@ unsafe impl<Kind> Send for ProcessingBlock<Kind> where
     Kind: processing_block_kind::ProcessingBlockKind
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{FutureExt, StreamExt};
+
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Builds a block on dangling pointers, which must not be passed to librealsense.
+    fn dangling_block() -> CustomProcessingBlock {
+        unsafe {
+            let queue = FrameQueue::from_raw(NonNull::dangling().as_ptr());
+            CustomProcessingBlock::from_raw_parts(NonNull::dangling().as_ptr(), queue)
+        }
+    }
+
+    /// Releases the block without deleting the dangling pointers.
+    fn forget_block<Kind>(block: ProcessingBlock<Kind>)
+    where
+        Kind: processing_block_kind::ProcessingBlockKind,
+    {
+        mem::forget(block.into_raw_parts());
+    }
+
+    #[test]
+    fn processor_survives_kind_change() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let error = Arc::new(Mutex::new(None));
+        let mut block = dangling_block();
+        block.processor = Some(ProcessorHandle {
+            _context: CallbackContext::new(Box::new(DropFlag(dropped.clone()))),
+            error: error.clone(),
+        });
+
+        // the round trip taken by into_any() and try_extend()
+        let mut block: CustomProcessingBlock = block.into_any().into_kind();
+        *error.lock().unwrap() = Some(Error::InvalidData("processor error".into()));
+        assert!(block.take_processor_error().is_some());
+        assert!(!dropped.load(Ordering::SeqCst));
+
+        let processor = block.processor.take();
+        forget_block(block);
+        drop(processor);
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn output_stream_ends_after_kind_change() {
+        let (mut tx, mut rx) = futures::channel::mpsc::channel(1);
+        let (_, context) = new_frame_callback(move |frame| {
+            let _ = tx.try_send(frame);
+        });
+        let mut block = dangling_block();
+        block.output = Some(context);
+
+        // the round trip taken by into_any() and try_extend()
+        let mut block: CustomProcessingBlock = block.into_any().into_kind();
+        assert!(block.check_queue_output().is_err());
+        assert!(rx.next().now_or_never().is_none());
+
+        let output = block.output.take();
+        forget_block(block);
+        drop(output);
+        assert!(matches!(rx.next().now_or_never(), Some(None)));
+    }
+}
@@ -63,6 +63,20 @@ impl ExtendableProcessingBlockKind for ZeroOrderFilterKind {
     const EXTENSION: Extension = Extension::ZeroOrderFilter;
 }
 
+#[derive(Debug)]
+pub struct HdrMergeKind;
+impl ProcessingBlockKind for HdrMergeKind {}
+impl ExtendableProcessingBlockKind for HdrMergeKind {
+    const EXTENSION: Extension = Extension::HdrMerge;
+}
+
+#[derive(Debug)]
+pub struct SequenceIdFilterKind;
+impl ProcessingBlockKind for SequenceIdFilterKind {}
+impl ExtendableProcessingBlockKind for SequenceIdFilterKind {
+    const EXTENSION: Extension = Extension::SequenceIdFilter;
+}
+
 #[derive(Debug)]
 pub struct PointCloudKind;
 impl ProcessingBlockKind for PointCloudKind {}
@@ -94,3 +108,7 @@ impl ProcessingBlockKind for HuffmanDepthDecompressKind {}
 #[derive(Debug)]
 pub struct RatesPrinterKind;
 impl ProcessingBlockKind for RatesPrinterKind {}
+
+#[derive(Debug)]
+pub struct CustomKind;
+impl ProcessingBlockKind for CustomKind {}
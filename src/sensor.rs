@@ -1,13 +1,17 @@
 //! Defines the sensor type.
 
 use crate::{
+    callback::{new_frame_callback, CallbackContext},
     common::*,
     device::Device,
     error::{ErrorChecker, Result},
+    frame::AnyFrame,
     kind::{CameraInfo, Rs2Option},
     options::ToOptions,
     processing_block_list::ProcessingBlockList,
     sensor_kind,
+    stream_profile::StreamProfile,
+    stream_profile_kind::StreamProfileKind,
     stream_profile_list::StreamProfileList,
 };
 
@@ -105,6 +109,7 @@ where
     Kind: sensor_kind::SensorKind,
 {
     pub(crate) ptr: NonNull<sys::rs2_sensor>,
+    callback: Option<CallbackContext>,
     _phantom: PhantomData<Kind>,
 }
 
@@ -274,7 +279,102 @@ where
         Ok(val != 0)
     }
 
-    pub fn into_raw(self) -> *mut sys::rs2_sensor {
+    /// Opens the sensor for exclusive access with a stream profile.
+    pub fn open<P>(&mut self, profile: &StreamProfile<P>) -> Result<()>
+    where
+        P: StreamProfileKind,
+    {
+        unsafe {
+            let mut checker = ErrorChecker::new();
+            sys::rs2_open(
+                self.ptr.as_ptr(),
+                profile.ptr().as_ptr(),
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+        }
+        Ok(())
+    }
+
+    /// Opens the sensor for exclusive access with multiple stream profiles.
+    pub fn open_multiple<P>(&mut self, profiles: &[StreamProfile<P>]) -> Result<()>
+    where
+        P: StreamProfileKind,
+    {
+        let mut profile_ptrs = profiles
+            .iter()
+            .map(|profile| profile.ptr().as_ptr() as *const _)
+            .collect::<Vec<_>>();
+        unsafe {
+            let mut checker = ErrorChecker::new();
+            sys::rs2_open_multiple(
+                self.ptr.as_ptr(),
+                profile_ptrs.as_mut_ptr(),
+                profile_ptrs.len() as c_int,
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+        }
+        Ok(())
+    }
+
+    /// Closes the sensor opened by [open](Sensor::open) or [open_multiple](Sensor::open_multiple).
+    pub fn close(&mut self) -> Result<()> {
+        unsafe {
+            let mut checker = ErrorChecker::new();
+            sys::rs2_close(self.ptr.as_ptr(), checker.inner_mut_ptr());
+            checker.check()?;
+        }
+        Ok(())
+    }
+
+    /// Starts streaming from the opened sensor.
+    ///
+    /// The callback is called on the librealsense thread for every frame.
+    /// The streaming is stopped when [stop](Sensor::stop) is called or the sensor is dropped.
+    pub fn start<F>(&mut self, callback: F) -> Result<()>
+    where
+        F: FnMut(AnyFrame) + Send + 'static,
+    {
+        let (callback, context) = new_frame_callback(callback);
+        unsafe {
+            let mut checker = ErrorChecker::new();
+            sys::rs2_start(
+                self.ptr.as_ptr(),
+                callback,
+                context.as_ptr(),
+                checker.inner_mut_ptr(),
+            );
+            checker.check()?;
+        }
+        self.callback = Some(context);
+        Ok(())
+    }
+
+    /// Stops streaming started by [start](Sensor::start).
+    pub fn stop(&mut self) -> Result<()> {
+        unsafe {
+            let mut checker = ErrorChecker::new();
+            sys::rs2_stop(self.ptr.as_ptr(), checker.inner_mut_ptr());
+            checker.check()?;
+        }
+        self.callback = None;
+        Ok(())
+    }
+
+    /// Destructs and returns the raw pointer.
+    ///
+    /// The sensor is stopped if it is streaming to a callback passed to [start](Sensor::start),
+    /// since the callback is freed along with the wrapper.
+    pub fn into_raw(mut self) -> *mut sys::rs2_sensor {
+        if let Some(callback) = self.callback.take() {
+            unsafe {
+                let mut checker = ErrorChecker::new();
+                sys::rs2_stop(self.ptr.as_ptr(), checker.inner_mut_ptr());
+                let _ = checker.check();
+            }
+            drop(callback);
+        }
         let ptr = self.ptr;
         mem::forget(self);
         ptr.as_ptr()
@@ -283,6 +383,7 @@ where
     pub unsafe fn from_raw(ptr: *mut sys::rs2_sensor) -> Self {
         Self {
             ptr: NonNull::new(ptr).unwrap(),
+            callback: None,
             _phantom: PhantomData,
         }
     }
@@ -306,14 +407,18 @@ impl AnySensor {
     }
 
     /// Extends to a specific sensor subtype.
-    pub fn try_extend_to<Kind>(self) -> Result<result::Result<Sensor<Kind>, Self>>
+    ///
+    /// The streaming started by [start](Sensor::start) goes on with the extended sensor.
+    pub fn try_extend_to<Kind>(mut self) -> Result<result::Result<Sensor<Kind>, Self>>
     where
         Kind: sensor_kind::NonAnySensorKind,
     {
         if self.is_extendable_to::<Kind>()? {
+            let callback = self.callback.take();
             let ptr = self.into_raw();
             let sensor = Sensor {
                 ptr: NonNull::new(ptr).unwrap(),
+                callback,
                 _phantom: PhantomData,
             };
             Ok(Ok(sensor))
@@ -399,6 +504,12 @@ where
 {
     fn drop(&mut self) {
         unsafe {
+            // the callback must outlive the streaming
+            if self.callback.is_some() {
+                let mut checker = ErrorChecker::new();
+                sys::rs2_stop(self.ptr.as_ptr(), checker.inner_mut_ptr());
+                let _ = checker.check();
+            }
             sys::rs2_delete_sensor(self.ptr.as_ptr());
         }
     }
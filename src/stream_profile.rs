@@ -39,6 +39,10 @@ impl<Kind> StreamProfile<Kind>
 where
     Kind: stream_profile_kind::StreamProfileKind,
 {
+    pub(crate) fn ptr(&self) -> NonNull<sys::rs2_stream_profile> {
+        self.ptr
+    }
+
     /// Check whether the profile is default or not.
     pub fn is_default(&self) -> Result<bool> {
         unsafe {
@@ -271,3 +275,8 @@ where
 }
 
 unsafe impl<Kind> Send for StreamProfile<Kind> where Kind: stream_profile_kind::StreamProfileKind {}
+
+// SAFETY: The shared getters read the stream, format, index, resolution and intrinsics
+// fixed when the profile is created. `get_extrinsics` and `set_extrinsics` go through the
+// global extrinsics graph of librealsense, which guards its nodes by its internal mutex.
+unsafe impl<Kind> Sync for StreamProfile<Kind> where Kind: stream_profile_kind::StreamProfileKind {}
@@ -0,0 +1,414 @@
+//! Collects frame rate, drop and latency statistics of streams.
+
+use crate::{
+    base::StreamProfileData,
+    common::*,
+    error::Result,
+    frame::{CompositeFrame, GenericFrameEx},
+    kind::{FrameMetaDataValue, TimestampDomain},
+};
+
+/// The default width of jitter histogram bins.
+pub const DEFAULT_JITTER_BIN_WIDTH: Duration = Duration::from_millis(1);
+
+/// The default number of jitter histogram bins.
+pub const DEFAULT_JITTER_NUM_BINS: usize = 32;
+
+/// Collects statistics of frames for each stream.
+///
+/// Frames are fed by [record](StreamStats::record) or [record_composite](StreamStats::record_composite),
+/// and are grouped by the stream kind and index of their stream profiles. A decreasing frame
+/// counter is regarded as a restart of the stream, which restarts the frame count and the
+/// effective frame rate.
+#[derive(Debug, Clone)]
+pub struct StreamStats {
+    bin_width: Duration,
+    num_bins: usize,
+    streams: HashMap<(StreamKind, usize), StreamState>,
+}
+
+#[derive(Debug, Clone)]
+struct StreamState {
+    framerate: usize,
+    num_frames: u64,
+    num_dropped: u64,
+    first_timestamp: f64,
+    last_timestamp: f64,
+    last_counter: Option<u64>,
+    latency: Option<LatencyStats>,
+    jitter: JitterHistogram,
+}
+
+/// The statistics of a stream reported by [StreamStats].
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamReport {
+    /// The stream kind in the stream profile.
+    pub stream: StreamKind,
+    /// The stream index in the stream profile, which is 0 for streams without an index.
+    pub index: usize,
+    /// The frame rate in the stream profile.
+    pub framerate: usize,
+    /// The number of received frames since the stream is started.
+    pub num_frames: u64,
+    /// The number of frames missing from the frame counter sequence.
+    pub num_dropped: u64,
+    /// The effective frame rate computed from frame timestamps.
+    pub fps: Option<f64>,
+    /// The time from frame capture to arrival on host.
+    ///
+    /// It is available only if frame timestamps are in host-synchronized domains.
+    pub latency: Option<LatencyStats>,
+    /// The deviations of frame intervals from the nominal interval.
+    pub jitter: JitterHistogram,
+}
+
+impl StreamReport {
+    /// Gets the ratio of dropped frames to expected frames.
+    pub fn drop_rate(&self) -> f64 {
+        let expected = self.num_frames + self.num_dropped;
+        if expected == 0 {
+            0.0
+        } else {
+            self.num_dropped as f64 / expected as f64
+        }
+    }
+}
+
+/// The minimum, maximum and mean of latencies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyStats {
+    /// The minimum latency.
+    pub min: Duration,
+    /// The maximum latency.
+    pub max: Duration,
+    /// The mean latency.
+    pub mean: Duration,
+    /// The number of frames with known latency.
+    pub count: u64,
+}
+
+impl LatencyStats {
+    fn new(latency: Duration) -> Self {
+        Self {
+            min: latency,
+            max: latency,
+            mean: latency,
+            count: 1,
+        }
+    }
+
+    fn push(&mut self, latency: Duration) {
+        self.min = self.min.min(latency);
+        self.max = self.max.max(latency);
+        self.count += 1;
+        let mean = self.mean.as_secs_f64();
+        let mean = mean + (latency.as_secs_f64() - mean) / self.count as f64;
+        self.mean = Duration::from_secs_f64(mean);
+    }
+}
+
+/// The histogram of frame interval deviations.
+///
+/// The bin `i` counts deviations in `[i * bin_width, (i + 1) * bin_width)`, and the last bin
+/// also counts all larger deviations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JitterHistogram {
+    bin_width: Duration,
+    counts: Vec<u64>,
+}
+
+impl JitterHistogram {
+    fn new(bin_width: Duration, num_bins: usize) -> Self {
+        Self {
+            bin_width,
+            counts: vec![0; num_bins],
+        }
+    }
+
+    fn push(&mut self, deviation: Duration) {
+        let index = (deviation.as_secs_f64() / self.bin_width.as_secs_f64()) as usize;
+        let index = index.min(self.counts.len() - 1);
+        self.counts[index] += 1;
+    }
+
+    /// Gets the width of each bin.
+    pub fn bin_width(&self) -> Duration {
+        self.bin_width
+    }
+
+    /// Gets the counts of bins.
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Gets the total number of samples.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Iterates over pairs of the lower bound and the count of bins.
+    pub fn bins(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        let bin_width = self.bin_width;
+        self.counts
+            .iter()
+            .enumerate()
+            .map(move |(index, &count)| (bin_width * index as u32, count))
+    }
+}
+
+impl StreamStats {
+    /// Creates a collector with [DEFAULT_JITTER_NUM_BINS] jitter bins of [DEFAULT_JITTER_BIN_WIDTH].
+    pub fn new() -> Self {
+        Self::with_jitter_bins(DEFAULT_JITTER_BIN_WIDTH, DEFAULT_JITTER_NUM_BINS)
+    }
+
+    /// Creates a collector with custom jitter histogram bins.
+    pub fn with_jitter_bins(bin_width: Duration, num_bins: usize) -> Self {
+        Self {
+            bin_width: bin_width.max(Duration::from_micros(1)),
+            num_bins: num_bins.max(1),
+            streams: HashMap::new(),
+        }
+    }
+
+    /// Records a frame.
+    pub fn record<F>(&mut self, frame: &F) -> Result<()>
+    where
+        F: GenericFrameEx,
+    {
+        let StreamProfileData {
+            stream,
+            index,
+            framerate,
+            ..
+        } = frame.stream_profile()?.get_data()?;
+        let timestamp = frame.timestamp()?;
+        let counter = if frame.supports_metadata(FrameMetaDataValue::FrameCounter)? {
+            frame.metadata(FrameMetaDataValue::FrameCounter)?
+        } else {
+            frame.number()?
+        };
+        let latency = arrival_latency(frame, timestamp)?;
+
+        let (bin_width, num_bins) = (self.bin_width, self.num_bins);
+        self.streams
+            .entry((stream, index))
+            .or_insert_with(|| StreamState::new(framerate.max(0) as usize, bin_width, num_bins))
+            .push(timestamp, counter, latency);
+
+        Ok(())
+    }
+
+    /// Records all frames in a composite frame.
+    pub fn record_composite(&mut self, frames: &CompositeFrame) -> Result<()> {
+        for frame in frames.try_iter()? {
+            self.record(&frame?)?;
+        }
+        Ok(())
+    }
+
+    /// Gets the statistics of a stream.
+    pub fn report(&self, stream: StreamKind, index: usize) -> Option<StreamReport> {
+        let state = self.streams.get(&(stream, index))?;
+        Some(state.report(stream, index))
+    }
+
+    /// Gets the statistics of all recorded streams.
+    pub fn reports(&self) -> Vec<StreamReport> {
+        self.streams
+            .iter()
+            .map(|(&(stream, index), state)| state.report(stream, index))
+            .collect()
+    }
+
+    /// Discards all statistics.
+    pub fn reset(&mut self) {
+        self.streams.clear();
+    }
+}
+
+impl Default for StreamStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StreamState {
+    fn new(framerate: usize, bin_width: Duration, num_bins: usize) -> Self {
+        Self {
+            framerate,
+            num_frames: 0,
+            num_dropped: 0,
+            first_timestamp: 0.0,
+            last_timestamp: 0.0,
+            last_counter: None,
+            latency: None,
+            jitter: JitterHistogram::new(bin_width, num_bins),
+        }
+    }
+
+    /// Adds a frame with the timestamp in milliseconds and the frame counter.
+    fn push(&mut self, timestamp: f64, counter: u64, latency: Option<Duration>) {
+        // the number of frame periods since the last frame
+        let steps = match self.last_counter {
+            Some(last_counter) if counter > last_counter => Some(counter - last_counter),
+            // a decreasing counter indicates the stream is restarted
+            Some(last_counter) if counter < last_counter => {
+                self.num_frames = 0;
+                None
+            }
+            _ => None,
+        };
+
+        if let Some(steps) = steps {
+            self.num_dropped += steps - 1;
+
+            if self.framerate > 0 {
+                // spread the interval over dropped frames
+                let interval = (timestamp - self.last_timestamp) / steps as f64;
+                let nominal = 1000.0 / self.framerate as f64;
+                let deviation = (interval - nominal).abs();
+                if deviation.is_finite() {
+                    self.jitter
+                        .push(Duration::from_secs_f64(deviation / 1000.0));
+                }
+            }
+        }
+
+        if self.num_frames == 0 {
+            self.first_timestamp = timestamp;
+        }
+        self.num_frames += 1;
+        self.last_timestamp = timestamp;
+        self.last_counter = Some(counter);
+
+        if let Some(latency) = latency {
+            match &mut self.latency {
+                Some(stats) => stats.push(latency),
+                None => self.latency = Some(LatencyStats::new(latency)),
+            }
+        }
+    }
+
+    fn report(&self, stream: StreamKind, index: usize) -> StreamReport {
+        let elapsed = self.last_timestamp - self.first_timestamp;
+        let fps = if self.num_frames > 1 && elapsed > 0.0 {
+            Some((self.num_frames - 1) as f64 * 1000.0 / elapsed)
+        } else {
+            None
+        };
+
+        StreamReport {
+            stream,
+            index,
+            framerate: self.framerate,
+            num_frames: self.num_frames,
+            num_dropped: self.num_dropped,
+            fps,
+            latency: self.latency,
+            jitter: self.jitter.clone(),
+        }
+    }
+}
+
+/// Computes the time from capture to arrival on host.
+///
+/// The arrival time is obtained from [TimeOfArrival](FrameMetaDataValue::TimeOfArrival) metadata.
+/// It returns `None` if the metadata is not supported.
+fn arrival_latency<F>(frame: &F, timestamp: f64) -> Result<Option<Duration>>
+where
+    F: GenericFrameEx,
+{
+    if frame.timestamp_domain()? == TimestampDomain::HardwareClock {
+        return Ok(None);
+    }
+    if !frame.supports_metadata(FrameMetaDataValue::TimeOfArrival)? {
+        return Ok(None);
+    }
+
+    let arrival = frame.metadata(FrameMetaDataValue::TimeOfArrival)? as f64;
+
+    let latency = (arrival - timestamp).max(0.0);
+    Ok(Some(Duration::from_secs_f64(latency / 1000.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn jitter_histogram_push() {
+        let mut histogram = JitterHistogram::new(MS, 3);
+        histogram.push(Duration::from_micros(0));
+        histogram.push(Duration::from_micros(999));
+        histogram.push(Duration::from_micros(1000));
+        histogram.push(Duration::from_micros(2500));
+        // overflow bin
+        histogram.push(Duration::from_millis(3));
+        histogram.push(Duration::from_secs(1));
+
+        assert_eq!(histogram.counts(), &[2, 1, 3]);
+        assert_eq!(histogram.total(), 6);
+        let bins: Vec<_> = histogram.bins().collect();
+        assert_eq!(
+            bins,
+            vec![(Duration::from_millis(0), 2), (MS, 1), (MS * 2, 3)]
+        );
+    }
+
+    #[test]
+    fn latency_stats_push() {
+        let mut stats = LatencyStats::new(MS * 10);
+        stats.push(MS * 20);
+        stats.push(MS * 30);
+
+        assert_eq!(stats.min, MS * 10);
+        assert_eq!(stats.max, MS * 30);
+        assert_eq!(stats.count, 3);
+        assert!((stats.mean.as_secs_f64() - 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stream_report_drop_rate() {
+        let mut report = StreamState::new(30, MS, 4).report(StreamKind::Depth, 0);
+        assert_eq!(report.drop_rate(), 0.0);
+
+        report.num_frames = 9;
+        report.num_dropped = 1;
+        assert!((report.drop_rate() - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stream_state_drops_and_jitter() {
+        let mut state = StreamState::new(10, MS, 4);
+        state.push(0.0, 1, None);
+        state.push(100.0, 2, None);
+        // two frames dropped, and the interval of 300 ms spans three periods
+        state.push(400.0, 5, None);
+        state.push(502.0, 6, None);
+
+        let report = state.report(StreamKind::Color, 0);
+        assert_eq!(report.num_frames, 4);
+        assert_eq!(report.num_dropped, 2);
+        assert_eq!(report.jitter.counts(), &[2, 0, 1, 0]);
+        assert!((report.fps.unwrap() - 3000.0 / 502.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn stream_state_restart() {
+        let mut state = StreamState::new(10, MS, 4);
+        state.push(1000.0, 100, None);
+        state.push(1100.0, 101, None);
+        // the counter and the timestamp start over
+        state.push(0.0, 1, None);
+        state.push(100.0, 2, None);
+
+        let report = state.report(StreamKind::Color, 0);
+        assert_eq!(report.num_frames, 2);
+        assert_eq!(report.num_dropped, 0);
+        assert_eq!(report.jitter.total(), 2);
+        assert!((report.fps.unwrap() - 10.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,315 @@
+//! Maps frame timestamps to the host clock.
+
+use crate::{
+    common::*,
+    error::{ErrorChecker, Result},
+    frame::GenericFrameEx,
+    kind::{FrameMetaDataValue, TimestampDomain},
+};
+use std::{
+    collections::VecDeque,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The default number of samples used to estimate the clock relation.
+pub const DEFAULT_TIME_SYNC_WINDOW: usize = 256;
+
+/// A device timestamp going backward by more than this number of milliseconds
+/// is regarded as a clock reset.
+const CLOCK_RESET_THRESHOLD_MS: f64 = 1000.0;
+
+/// Gets the current time of librealsense.
+///
+/// It is the system time in live streaming, and the recorded time in playback.
+pub fn host_time() -> Result<SystemTime> {
+    let millis = unsafe {
+        let mut checker = ErrorChecker::new();
+        let millis = sys::rs2_get_time(checker.inner_mut_ptr());
+        checker.check()?;
+        millis
+    };
+    Ok(millis_to_system_time(millis).unwrap_or(UNIX_EPOCH))
+}
+
+/// Converts frame timestamps in [HardwareClock](TimestampDomain::HardwareClock) and
+/// [GlobalTime](TimestampDomain::GlobalTime) domains to the host clock.
+///
+/// Each frame passed to [update](TimeSync::update) adds a sample pairing the frame timestamp
+/// with its arrival time on host, obtained from [BackendTimestamp](FrameMetaDataValue::BackendTimestamp)
+/// or [TimeOfArrival](FrameMetaDataValue::TimeOfArrival) metadata. The offset and drift
+/// between clocks are estimated by a linear fit over recent samples. Timestamps in
+/// [SystemTime](TimestampDomain::SystemTime) domain are already in host clock and are
+/// converted as is.
+///
+/// Since hardware clocks are per sensor, one instance should be used for each sensor.
+#[derive(Debug, Clone)]
+pub struct TimeSync {
+    window: usize,
+    fits: HashMap<TimestampDomain, ClockFit>,
+    anchor_instant: Instant,
+    anchor_millis: f64,
+}
+
+/// The samples of (device time, host time) pairs in milliseconds.
+#[derive(Debug, Clone, Default)]
+struct ClockFit {
+    samples: VecDeque<(f64, f64)>,
+}
+
+impl TimeSync {
+    /// Creates an instance with [DEFAULT_TIME_SYNC_WINDOW] samples.
+    pub fn new() -> Result<Self> {
+        Self::with_window(DEFAULT_TIME_SYNC_WINDOW)
+    }
+
+    /// Creates an instance estimating the clock relation from at most `window` recent samples.
+    pub fn with_window(window: usize) -> Result<Self> {
+        let anchor_instant = Instant::now();
+        let anchor_millis = system_time_to_millis(host_time()?);
+        let sync = Self {
+            window: window.max(2),
+            fits: HashMap::new(),
+            anchor_instant,
+            anchor_millis,
+        };
+        Ok(sync)
+    }
+
+    /// Adds a sample from the frame.
+    ///
+    /// It returns `false` if the frame is in system time domain, or has no arrival time metadata.
+    pub fn update<F>(&mut self, frame: &F) -> Result<bool>
+    where
+        F: GenericFrameEx,
+    {
+        let domain = frame.timestamp_domain()?;
+        if domain == TimestampDomain::SystemTime {
+            return Ok(false);
+        }
+        let arrival = match arrival_millis(frame)? {
+            Some(arrival) => arrival,
+            None => return Ok(false),
+        };
+        let device = frame.timestamp()?;
+
+        let window = self.window;
+        self.fits
+            .entry(domain)
+            .or_default()
+            .push(device, arrival, window);
+
+        Ok(true)
+    }
+
+    /// Discards all samples.
+    pub fn clear(&mut self) {
+        self.fits.clear();
+    }
+
+    /// Gets the number of samples of a timestamp domain.
+    pub fn num_samples(&self, domain: TimestampDomain) -> usize {
+        self.fits
+            .get(&domain)
+            .map(|fit| fit.samples.len())
+            .unwrap_or(0)
+    }
+
+    /// Gets the estimated rate of host clock with respect to device clock minus one.
+    ///
+    /// For example, a drift of `1e-5` means the host clock runs 10 microseconds faster
+    /// per second. It returns `None` if less than two samples are collected.
+    pub fn drift(&self, domain: TimestampDomain) -> Option<f64> {
+        let fit = self.fits.get(&domain)?;
+        if fit.samples.len() < 2 {
+            return None;
+        }
+        fit.line().map(|(_, _, slope)| slope - 1.0)
+    }
+
+    /// Converts a timestamp in milliseconds to host time in milliseconds since UNIX epoch.
+    ///
+    /// It returns `None` if no sample of the domain is collected.
+    pub fn to_host_millis(&self, timestamp: f64, domain: TimestampDomain) -> Option<f64> {
+        if domain == TimestampDomain::SystemTime {
+            return Some(timestamp);
+        }
+        let (mean_device, mean_host, slope) = self.fits.get(&domain)?.line()?;
+        Some(mean_host + slope * (timestamp - mean_device))
+    }
+
+    /// Converts the timestamp of a frame to [SystemTime].
+    pub fn system_time<F>(&self, frame: &F) -> Result<Option<SystemTime>>
+    where
+        F: GenericFrameEx,
+    {
+        let millis = self.to_host_millis(frame.timestamp()?, frame.timestamp_domain()?);
+        Ok(millis.and_then(millis_to_system_time))
+    }
+
+    /// Converts the timestamp of a frame to [Instant].
+    pub fn instant<F>(&self, frame: &F) -> Result<Option<Instant>>
+    where
+        F: GenericFrameEx,
+    {
+        let millis = self.to_host_millis(frame.timestamp()?, frame.timestamp_domain()?);
+        let instant = millis.and_then(|millis| {
+            let diff = millis - self.anchor_millis;
+            if diff >= 0.0 {
+                self.anchor_instant
+                    .checked_add(Duration::from_secs_f64(diff / 1000.0))
+            } else {
+                self.anchor_instant
+                    .checked_sub(Duration::from_secs_f64(-diff / 1000.0))
+            }
+        });
+        Ok(instant)
+    }
+}
+
+impl ClockFit {
+    /// Adds a sample and keeps at most `window` recent samples.
+    ///
+    /// The samples are discarded if the device time goes backward by more than
+    /// [CLOCK_RESET_THRESHOLD_MS], since the device clock is reset.
+    fn push(&mut self, device: f64, host: f64, window: usize) {
+        if let Some(&(last, _)) = self.samples.back() {
+            if device < last - CLOCK_RESET_THRESHOLD_MS {
+                self.samples.clear();
+            }
+        }
+        while self.samples.len() >= window.max(1) {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((device, host));
+    }
+
+    /// Fits `host = mean_host + slope * (device - mean_device)` by least squares.
+    ///
+    /// The slope is assumed to be one if device timestamps have no variance.
+    fn line(&self) -> Option<(f64, f64, f64)> {
+        let len = self.samples.len();
+        if len == 0 {
+            return None;
+        }
+        let mean_device = self.samples.iter().map(|&(device, _)| device).sum::<f64>() / len as f64;
+        let mean_host = self.samples.iter().map(|&(_, host)| host).sum::<f64>() / len as f64;
+        let (cov, var) = self
+            .samples
+            .iter()
+            .fold((0.0, 0.0), |(cov, var), &(device, host)| {
+                let dx = device - mean_device;
+                (cov + dx * (host - mean_host), var + dx * dx)
+            });
+        let slope = if var > f64::EPSILON { cov / var } else { 1.0 };
+        Some((mean_device, mean_host, slope))
+    }
+}
+
+/// Gets the arrival time of a frame on host in milliseconds since UNIX epoch.
+fn arrival_millis<F>(frame: &F) -> Result<Option<f64>>
+where
+    F: GenericFrameEx,
+{
+    for kind in [
+        FrameMetaDataValue::BackendTimestamp,
+        FrameMetaDataValue::TimeOfArrival,
+    ]
+    .iter()
+    .copied()
+    {
+        if frame.supports_metadata(kind)? {
+            let millis = frame.metadata(kind)?;
+            if millis != 0 {
+                return Ok(Some(millis as f64));
+            }
+        }
+    }
+    Ok(None)
+}
+
+fn millis_to_system_time(millis: f64) -> Option<SystemTime> {
+    if millis.is_finite() && millis >= 0.0 {
+        UNIX_EPOCH.checked_add(Duration::from_secs_f64(millis / 1000.0))
+    } else {
+        None
+    }
+}
+
+fn system_time_to_millis(time: SystemTime) -> f64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs_f64() * 1000.0,
+        Err(err) => -err.duration().as_secs_f64() * 1000.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(lhs: f64, rhs: f64) {
+        assert!((lhs - rhs).abs() < 1e-6, "{} != {}", lhs, rhs);
+    }
+
+    #[test]
+    fn clock_fit_line() {
+        let mut fit = ClockFit::default();
+        assert!(fit.line().is_none());
+
+        // host = 1000 + 1.001 * device
+        for device in (0..10).map(|step| step as f64 * 33.0) {
+            fit.push(device, 1000.0 + 1.001 * device, 16);
+        }
+        let (mean_device, mean_host, slope) = fit.line().unwrap();
+        assert_close(slope, 1.001);
+        assert_close(mean_host, 1000.0 + 1.001 * mean_device);
+    }
+
+    #[test]
+    fn clock_fit_least_squares() {
+        let mut fit = ClockFit::default();
+        // symmetric noise around host = device + 50
+        for &(device, noise) in [(0.0, 1.0), (10.0, -1.0), (20.0, -1.0), (30.0, 1.0)].iter() {
+            fit.push(device, device + 50.0 + noise, 16);
+        }
+        let (mean_device, mean_host, slope) = fit.line().unwrap();
+        assert_close(mean_device, 15.0);
+        assert_close(mean_host, 65.0);
+        assert_close(slope, 1.0);
+    }
+
+    #[test]
+    fn clock_fit_constant_device_time() {
+        let mut fit = ClockFit::default();
+        fit.push(5.0, 100.0, 16);
+        fit.push(5.0, 102.0, 16);
+        let (_, mean_host, slope) = fit.line().unwrap();
+        assert_close(mean_host, 101.0);
+        assert_close(slope, 1.0);
+    }
+
+    #[test]
+    fn clock_fit_window() {
+        let mut fit = ClockFit::default();
+        for device in 0..5 {
+            fit.push(device as f64, device as f64, 3);
+        }
+        let devices: Vec<_> = fit.samples.iter().map(|&(device, _)| device).collect();
+        assert_eq!(devices, vec![2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn clock_fit_reset() {
+        let mut fit = ClockFit::default();
+        fit.push(5000.0, 1.0, 16);
+        fit.push(5100.0, 2.0, 16);
+
+        // small backward jumps are kept
+        fit.push(4200.0, 3.0, 16);
+        assert_eq!(fit.samples.len(), 3);
+
+        // the clock is reset when going back by more than 1000 ms
+        fit.push(3100.0, 4.0, 16);
+        assert_eq!(fit.samples.len(), 1);
+        assert_eq!(fit.samples[0], (3100.0, 4.0));
+    }
+}
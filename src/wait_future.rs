@@ -0,0 +1,216 @@
+//! Defines the cancellation-safe future of blocking waits.
+
+use crate::{blocking, common::*};
+use futures::{future::FusedFuture, task::AtomicWaker};
+use std::{future::Future, time::Instant};
+
+/// The longest blocking call made by the waiter thread before checking cancellation.
+const WAIT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The future returned by asynchronous waits, such as
+/// [FrameQueue::wait_async](crate::frame_queue::FrameQueue::wait_async) and
+/// [ActivePipeline::wait_async](crate::pipeline::Pipeline::wait_async).
+///
+/// The blocking wait runs on a background thread selected by the [blocking](crate::blocking)
+/// module. The thread shares the ownership of the waited object, so the wait stays valid
+/// even if the object is dropped or the future is leaked. Dropping the future returns
+/// immediately, and the thread leaves the wait in the background within 100 milliseconds.
+///
+/// The future implements [FusedFuture] and [Unpin], and can be used in `select!` loops by reference.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitFuture<T>
+where
+    T: Send + 'static,
+{
+    shared: Arc<WaitShared<T>>,
+    terminated: bool,
+}
+
+#[derive(Debug)]
+struct WaitShared<T> {
+    output: Mutex<Option<T>>,
+    waker: AtomicWaker,
+    cancelled: AtomicBool,
+    panicked: AtomicBool,
+}
+
+/// Wakes the future if the wait panics, so that the panic is propagated to the poller.
+struct PanicGuard<'a, T>(&'a WaitShared<T>);
+
+impl<'a, T> Drop for PanicGuard<'a, T> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.panicked.store(true, Ordering::SeqCst);
+            self.0.waker.wake();
+        }
+    }
+}
+
+impl<T> WaitFuture<T>
+where
+    T: Send + 'static,
+{
+    /// Spawns the background wait.
+    ///
+    /// The wait repeatedly calls `wait_fn(interval, is_last)` until it returns the output.
+    /// The `is_last` flag is set when `timeout` elapses after the call, and then
+    /// `wait_fn` must return the output. The closure must own the waited object.
+    /// If `wait_fn` panics, polling the future panics as well.
+    pub(crate) fn new<F>(timeout: Option<Duration>, mut wait_fn: F) -> Self
+    where
+        F: FnMut(Duration, bool) -> Option<T> + Send + 'static,
+    {
+        let shared = Arc::new(WaitShared {
+            output: Mutex::new(None),
+            waker: AtomicWaker::new(),
+            cancelled: AtomicBool::new(false),
+            panicked: AtomicBool::new(false),
+        });
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        {
+            let shared = shared.clone();
+            blocking::spawn_blocking(move || {
+                let _guard = PanicGuard(&shared);
+
+                // the future may be dropped before the job starts
+                while !shared.cancelled.load(Ordering::SeqCst) {
+                    let (interval, is_last) = match deadline {
+                        Some(deadline) => {
+                            let remaining = deadline.saturating_duration_since(Instant::now());
+                            (remaining.min(WAIT_INTERVAL), remaining <= WAIT_INTERVAL)
+                        }
+                        None => (WAIT_INTERVAL, false),
+                    };
+
+                    if let Some(output) = wait_fn(interval, is_last) {
+                        *shared.output.lock().unwrap() = Some(output);
+                        shared.waker.wake();
+                        break;
+                    }
+                }
+            });
+        }
+
+        Self {
+            shared,
+            terminated: false,
+        }
+    }
+}
+
+impl<T> Future for WaitFuture<T>
+where
+    T: Send + 'static,
+{
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        assert!(!this.terminated, "WaitFuture is polled after completion");
+
+        // register before checking the output to avoid missing wake-ups
+        this.shared.waker.register(cx.waker());
+
+        let output = this.shared.output.lock().unwrap().take();
+        match output {
+            Some(output) => {
+                this.terminated = true;
+                Poll::Ready(output)
+            }
+            None if this.shared.panicked.load(Ordering::SeqCst) => {
+                this.terminated = true;
+                panic!("the background wait of WaitFuture panicked");
+            }
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<T> FusedFuture for WaitFuture<T>
+where
+    T: Send + 'static,
+{
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T> Drop for WaitFuture<T>
+where
+    T: Send + 'static,
+{
+    fn drop(&mut self) {
+        self.shared.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wait_future_output() {
+        let mut count = 0;
+        let future = WaitFuture::new(None, move |_, _| {
+            count += 1;
+            if count == 3 {
+                Some(count)
+            } else {
+                None
+            }
+        });
+        assert_eq!(futures::executor::block_on(future), 3);
+    }
+
+    #[test]
+    fn wait_future_timeout() {
+        let future = WaitFuture::new(Some(Duration::from_millis(10)), |interval, is_last| {
+            thread::sleep(interval);
+            if is_last {
+                Some(())
+            } else {
+                None
+            }
+        });
+        futures::executor::block_on(future);
+    }
+
+    #[test]
+    fn wait_future_cancel() {
+        struct DropFlag(Arc<AtomicBool>);
+
+        impl Drop for DropFlag {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        // the wait never completes, and is released only by cancellation
+        let released = Arc::new(AtomicBool::new(false));
+        let flag = DropFlag(released.clone());
+        let future = WaitFuture::<()>::new(None, move |interval, _| {
+            let _ = &flag;
+            thread::sleep(interval);
+            None
+        });
+        drop(future);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !released.load(Ordering::SeqCst) {
+            assert!(
+                Instant::now() < deadline,
+                "the cancelled wait is not released"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "the background wait of WaitFuture panicked")]
+    fn wait_future_panic() {
+        let future = WaitFuture::<()>::new(None, |_, _| panic!("wait failed"));
+        futures::executor::block_on(future);
+    }
+}
@@ -0,0 +1,83 @@
+//! Compile-time checks of Send and Sync bounds. The tests pass if the file compiles.
+
+use realsense_rust::{
+    frame::{AnyFrame, CompositeFrame, DepthFrame, MotionFrame, PoseFrame, VideoFrame},
+    processing_block::{Align, AnyProcessingBlock, Colorizer, CustomProcessingBlock},
+    stream_profile::{AnyStreamProfile, VideoStreamProfile},
+    ActivePipeline, AlignedFrames, AnySensor, CompositeFrameIntoIter, Config, Context, DepthSensor,
+    Device, DeviceList, Error, FilterChain, FrameQueue, FrameStream, FrameSyncer,
+    FrameSyncerSender, InactivePipeline, OwnedVideoFrame, PipelineFrameStream, PipelineProfile,
+    SensorList, StreamProfileList, WaitFuture,
+};
+
+fn assert_send<T: Send>() {}
+
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn frames_are_send_and_sync() {
+    assert_send::<AnyFrame>();
+    assert_sync::<AnyFrame>();
+    assert_send::<CompositeFrame>();
+    assert_sync::<CompositeFrame>();
+    assert_send::<VideoFrame>();
+    assert_sync::<VideoFrame>();
+    assert_send::<DepthFrame>();
+    assert_sync::<DepthFrame>();
+    assert_send::<MotionFrame>();
+    assert_sync::<MotionFrame>();
+    assert_send::<PoseFrame>();
+    assert_sync::<PoseFrame>();
+    assert_send::<AlignedFrames>();
+    assert_sync::<AlignedFrames>();
+    assert_send::<CompositeFrameIntoIter>();
+    assert_send::<OwnedVideoFrame>();
+    assert_sync::<OwnedVideoFrame>();
+}
+
+#[test]
+fn shared_handles_are_send_and_sync() {
+    assert_send::<Context>();
+    assert_sync::<Context>();
+    assert_send::<Device>();
+    assert_sync::<Device>();
+    assert_send::<InactivePipeline>();
+    assert_sync::<InactivePipeline>();
+    assert_send::<ActivePipeline>();
+    assert_sync::<ActivePipeline>();
+    assert_send::<PipelineProfile>();
+    assert_sync::<PipelineProfile>();
+    assert_send::<AnyStreamProfile>();
+    assert_sync::<AnyStreamProfile>();
+    assert_send::<VideoStreamProfile>();
+    assert_sync::<VideoStreamProfile>();
+    assert_send::<FrameQueue>();
+    assert_sync::<FrameQueue>();
+    assert_send::<FrameSyncerSender>();
+    assert_sync::<FrameSyncerSender>();
+    assert_send::<Error>();
+    assert_sync::<Error>();
+}
+
+#[test]
+fn exclusive_handles_are_send() {
+    assert_send::<AnySensor>();
+    assert_send::<DepthSensor>();
+    assert_send::<AnyProcessingBlock>();
+    assert_send::<Align>();
+    assert_send::<Colorizer>();
+    assert_send::<CustomProcessingBlock>();
+    assert_send::<FilterChain>();
+    assert_send::<FrameSyncer>();
+    assert_send::<Config>();
+    assert_send::<DeviceList>();
+    assert_send::<SensorList>();
+    assert_send::<StreamProfileList>();
+}
+
+#[test]
+fn streams_and_futures_are_send() {
+    assert_send::<FrameStream>();
+    assert_send::<PipelineFrameStream<'static>>();
+    assert_send::<WaitFuture<Result<AnyFrame, Error>>>();
+}